@@ -1,23 +1,34 @@
-use crate::{Entry, Item};
+use crate::{
+    decode::{DecodedSection, Registry},
+    overlay::Spec,
+    state::ViewState,
+    Entry, Item, SortOrder,
+};
 
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeSet, HashMap},
+    io::Write,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
+};
 
 use ratatui::{
     crossterm::event::{
         self, Event, KeyCode, KeyEventKind, MouseButton, MouseEvent,
         MouseEventKind,
     },
-    layout::{Alignment, Constraint, Layout, Margin, Rect},
+    layout::{Alignment, Constraint, Flex, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
     widgets::{
-        Block, Borders, Cell, Paragraph, Row, Scrollbar, ScrollbarOrientation,
-        ScrollbarState, Table, TableState,
+        Block, Borders, Cell, Clear, Paragraph, Row, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, Table, TableState, Wrap,
     },
     Frame,
 };
-use zerocopy::FromBytes;
 
+#[derive(Copy, Clone)]
 enum DataGrouping {
     Byte,
     Word,
@@ -30,6 +41,361 @@ enum Endian {
     Big,
 }
 
+/// Pane which receives movement keys
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Focus {
+    Items,
+    Data,
+    /// Table in the decoded view below the data pane
+    Specialized,
+}
+
+/// How each group of bytes in the data pane is displayed
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum DataInterp {
+    Hex,
+    Signed,
+    /// IEEE-754 float, only valid for 4- and 8-byte groups
+    Float,
+}
+
+/// How bytes are shown in the data pane's text column
+#[derive(Copy, Clone)]
+enum TextEncoding {
+    Ascii,
+    /// Pairs of bytes as little-endian UTF-16 code units
+    Utf16Le,
+    /// Printable ISO 8859-1 characters
+    Latin1,
+}
+
+impl TextEncoding {
+    fn name(&self) -> &'static str {
+        match self {
+            TextEncoding::Ascii => "ascii",
+            TextEncoding::Utf16Le => "utf-16le",
+            TextEncoding::Latin1 => "latin-1",
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            TextEncoding::Ascii => TextEncoding::Utf16Le,
+            TextEncoding::Utf16Le => TextEncoding::Latin1,
+            TextEncoding::Latin1 => TextEncoding::Ascii,
+        }
+    }
+
+    /// Decodes a row of bytes, replacing non-printable characters with `.`
+    fn decode(&self, bytes: &[u8]) -> String {
+        let printable = |c: char| if c.is_control() { '.' } else { c };
+        match self {
+            TextEncoding::Ascii => bytes
+                .iter()
+                .map(|b| {
+                    if b.is_ascii() && !b.is_ascii_control() {
+                        *b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect(),
+            TextEncoding::Utf16Le => bytes
+                .chunks(2)
+                .map(|c| match c {
+                    [lo, hi] => {
+                        char::from_u32(u32::from(*lo) | u32::from(*hi) << 8)
+                            .map_or('.', printable)
+                    }
+                    _ => '.',
+                })
+                .collect(),
+            TextEncoding::Latin1 => {
+                bytes.iter().map(|b| printable(char::from(*b))).collect()
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Action {
+    Top,
+    Group(DataGrouping),
+    Endian,
+    Signed,
+    Float,
+    Text,
+    Quit,
+    Down,
+    Up,
+    FocusData,
+    FocusItems,
+    CycleFocus,
+    Colors,
+    Squeeze,
+    Strings,
+    FullEntry,
+    StructOverlay,
+    DecodeAs,
+    GotoOffset,
+    HideCancelled,
+    HideSynthetic,
+    Sort,
+    CopyRaw,
+    CopyDecoded,
+    WriteView,
+    PageDown,
+    PageUp,
+    NextInGroup,
+    PrevInGroup,
+    Bookmark,
+    NextBookmark,
+    PrevBookmark,
+    ShrinkTable,
+    GrowTable,
+    Help,
+}
+
+struct Binding {
+    keys: &'static [KeyCode],
+    /// Key names, as shown in the help overlay
+    label: &'static str,
+    help: &'static str,
+    action: Action,
+}
+
+/// Key bindings, used both to dispatch key presses and to render help
+const BINDINGS: &[Binding] = &[
+    Binding {
+        keys: &[KeyCode::Char('?')],
+        label: "?",
+        help: "Show or hide this help",
+        action: Action::Help,
+    },
+    Binding {
+        keys: &[KeyCode::Char('q'), KeyCode::Esc],
+        label: "q / Esc",
+        help: "Quit",
+        action: Action::Quit,
+    },
+    Binding {
+        keys: &[KeyCode::Char('j'), KeyCode::Down],
+        label: "j / Down",
+        help: "Move down in the focused pane",
+        action: Action::Down,
+    },
+    Binding {
+        keys: &[KeyCode::Char('k'), KeyCode::Up],
+        label: "k / Up",
+        help: "Move up in the focused pane",
+        action: Action::Up,
+    },
+    Binding {
+        keys: &[KeyCode::PageDown],
+        label: "PgDn",
+        help: "Move down one page in the focused pane",
+        action: Action::PageDown,
+    },
+    Binding {
+        keys: &[KeyCode::PageUp],
+        label: "PgUp",
+        help: "Move up one page in the focused pane",
+        action: Action::PageUp,
+    },
+    Binding {
+        keys: &[KeyCode::Char('}')],
+        label: "}",
+        help: "Jump to the next entry in the same group",
+        action: Action::NextInGroup,
+    },
+    Binding {
+        keys: &[KeyCode::Char('{')],
+        label: "{",
+        help: "Jump to the previous entry in the same group",
+        action: Action::PrevInGroup,
+    },
+    Binding {
+        keys: &[KeyCode::Char('m')],
+        label: "m",
+        help: "Bookmark or unbookmark the selected entry",
+        action: Action::Bookmark,
+    },
+    Binding {
+        keys: &[KeyCode::Char('\'')],
+        label: "'",
+        help: "Jump to the next bookmarked entry",
+        action: Action::NextBookmark,
+    },
+    Binding {
+        keys: &[KeyCode::Char('`')],
+        label: "`",
+        help: "Jump to the previous bookmarked entry",
+        action: Action::PrevBookmark,
+    },
+    Binding {
+        keys: &[KeyCode::Char('0')],
+        label: "0",
+        help: "Jump to the top of the focused pane",
+        action: Action::Top,
+    },
+    Binding {
+        keys: &[KeyCode::Char('h'), KeyCode::Left],
+        label: "h / Left",
+        help: "Focus the entry list",
+        action: Action::FocusItems,
+    },
+    Binding {
+        keys: &[KeyCode::Char('l'), KeyCode::Right],
+        label: "l / Right",
+        help: "Focus the data pane",
+        action: Action::FocusData,
+    },
+    Binding {
+        keys: &[KeyCode::Tab],
+        label: "Tab",
+        help: "Cycle focus between the entry list, data pane, and decoded view",
+        action: Action::CycleFocus,
+    },
+    Binding {
+        keys: &[KeyCode::Char('1')],
+        label: "1",
+        help: "Group data as bytes",
+        action: Action::Group(DataGrouping::Byte),
+    },
+    Binding {
+        keys: &[KeyCode::Char('2')],
+        label: "2",
+        help: "Group data as 2-byte words",
+        action: Action::Group(DataGrouping::Word),
+    },
+    Binding {
+        keys: &[KeyCode::Char('4')],
+        label: "4",
+        help: "Group data as 4-byte words",
+        action: Action::Group(DataGrouping::DoubleWord),
+    },
+    Binding {
+        keys: &[KeyCode::Char('8')],
+        label: "8",
+        help: "Group data as 8-byte words",
+        action: Action::Group(DataGrouping::QuadWord),
+    },
+    Binding {
+        keys: &[KeyCode::Char('e')],
+        label: "e",
+        help: "Toggle little / big endian",
+        action: Action::Endian,
+    },
+    Binding {
+        keys: &[KeyCode::Char('s')],
+        label: "s",
+        help: "Toggle signed integer display",
+        action: Action::Signed,
+    },
+    Binding {
+        keys: &[KeyCode::Char('f')],
+        label: "f",
+        help: "Toggle float display (4- and 8-byte groups)",
+        action: Action::Float,
+    },
+    Binding {
+        keys: &[KeyCode::Char('t')],
+        label: "t",
+        help: "Cycle text column encoding (ASCII, UTF-16LE, Latin-1)",
+        action: Action::Text,
+    },
+    Binding {
+        keys: &[KeyCode::Char('c')],
+        label: "c",
+        help: "Toggle data coloring",
+        action: Action::Colors,
+    },
+    Binding {
+        keys: &[KeyCode::Char('z')],
+        label: "z",
+        help: "Squeeze runs of all-zero rows in the data pane",
+        action: Action::Squeeze,
+    },
+    Binding {
+        keys: &[KeyCode::Char('p')],
+        label: "p",
+        help: "List printable strings in the data pane, like `strings`",
+        action: Action::Strings,
+    },
+    Binding {
+        keys: &[KeyCode::Char('H')],
+        label: "H",
+        help: "Show or hide the entry header (and HMAC) in the data pane",
+        action: Action::FullEntry,
+    },
+    Binding {
+        keys: &[KeyCode::Char('S')],
+        label: "S",
+        help: "Show entry data as fields (like u32,u64,[u8;16])",
+        action: Action::StructOverlay,
+    },
+    Binding {
+        keys: &[KeyCode::Char('D')],
+        label: "D",
+        help: "Decode the selected entry as a type of your choosing",
+        action: Action::DecodeAs,
+    },
+    Binding {
+        keys: &[KeyCode::Char('[')],
+        label: "[",
+        help: "Shrink the entry table (or drag its border)",
+        action: Action::ShrinkTable,
+    },
+    Binding {
+        keys: &[KeyCode::Char(']')],
+        label: "]",
+        help: "Grow the entry table",
+        action: Action::GrowTable,
+    },
+    Binding {
+        keys: &[KeyCode::Char('g')],
+        label: "g",
+        help: "Go to a file offset",
+        action: Action::GotoOffset,
+    },
+    Binding {
+        keys: &[KeyCode::Char('x')],
+        label: "x",
+        help: "Show or hide cancelled entries",
+        action: Action::HideCancelled,
+    },
+    Binding {
+        keys: &[KeyCode::Char('v')],
+        label: "v",
+        help: "Show or hide the blob header and padding",
+        action: Action::HideSynthetic,
+    },
+    Binding {
+        keys: &[KeyCode::Char('o')],
+        label: "o",
+        help: "Toggle between file order and sorting by group",
+        action: Action::Sort,
+    },
+    Binding {
+        keys: &[KeyCode::Char('y')],
+        label: "y",
+        help: "Copy the selected entry's data as hex",
+        action: Action::CopyRaw,
+    },
+    Binding {
+        keys: &[KeyCode::Char('Y')],
+        label: "Y",
+        help: "Copy the selected entry's decoded text",
+        action: Action::CopyDecoded,
+    },
+    Binding {
+        keys: &[KeyCode::Char('w')],
+        label: "w",
+        help: "Write the selected entry's decoded text (or hex) to a file",
+        action: Action::WriteView,
+    },
+];
+
 #[derive(strum_macros::EnumDiscriminants)]
 #[strum_discriminants(name(SpecializedTag))]
 enum SpecializedState {
@@ -37,6 +403,26 @@ enum SpecializedState {
     EventLog(TableState),
     MemMap(TableState),
     PmuTrainingFailure(TableState),
+    Coremap(TableState),
+    Smbios(TableState),
+    /// Runs of printable text, listed in place of any decoded view
+    Strings(TableState),
+    /// Plain text from the `--decode` formatter
+    Text,
+}
+
+impl SpecializedState {
+    fn table(&mut self) -> Option<&mut TableState> {
+        match self {
+            SpecializedState::EventLog(t)
+            | SpecializedState::MemMap(t)
+            | SpecializedState::PmuTrainingFailure(t)
+            | SpecializedState::Coremap(t)
+            | SpecializedState::Smbios(t)
+            | SpecializedState::Strings(t) => Some(t),
+            SpecializedState::Header | SpecializedState::Text => None,
+        }
+    }
 }
 
 impl DataGrouping {
@@ -48,44 +434,257 @@ impl DataGrouping {
             DataGrouping::QuadWord => 8,
         }
     }
+
+    /// Returns the display width of a group in the given interpretation
+    fn columns(&self, interp: DataInterp) -> usize {
+        match interp {
+            DataInterp::Hex => self.bytes() * 2,
+            DataInterp::Signed => match self {
+                DataGrouping::Byte => 4,
+                DataGrouping::Word => 6,
+                DataGrouping::DoubleWord => 11,
+                DataGrouping::QuadWord => 20,
+            },
+            // Enough for "-1.234e-308"
+            DataInterp::Float => 11,
+        }
+    }
+
+    fn from_bytes(bytes: usize) -> Option<Self> {
+        match bytes {
+            1 => Some(DataGrouping::Byte),
+            2 => Some(DataGrouping::Word),
+            4 => Some(DataGrouping::DoubleWord),
+            8 => Some(DataGrouping::QuadWord),
+            _ => None,
+        }
+    }
+}
+
+/// Description of the loaded file, shown in the title bar
+pub struct FileInfo {
+    pub name: String,
+    /// Size of the file in bytes
+    pub size: usize,
+}
+
+/// Watches the loaded file and reloads it when it changes, for `--follow`
+pub struct Follow {
+    path: PathBuf,
+    /// Reads and filters the file again
+    reload: Box<dyn Fn() -> anyhow::Result<Vec<Entry>>>,
+    /// Modification time and length when the file was last loaded
+    stamp: Option<(SystemTime, u64)>,
+}
+
+impl Follow {
+    pub fn new(
+        path: PathBuf,
+        reload: Box<dyn Fn() -> anyhow::Result<Vec<Entry>>>,
+    ) -> Self {
+        let stamp = Self::stamp(&path);
+        Self {
+            path,
+            reload,
+            stamp,
+        }
+    }
+
+    fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+        let m = std::fs::metadata(path).ok()?;
+        Some((m.modified().ok()?, m.len()))
+    }
+
+    /// Checks whether the file has changed since it was last loaded
+    ///
+    /// The length is compared as well as the modification time, since the
+    /// latter may be coarse on some filesystems.
+    fn changed(&mut self) -> bool {
+        let stamp = Self::stamp(&self.path);
+        if stamp.is_some() && stamp != self.stamp {
+            self.stamp = stamp;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Items which are still being loaded, for huge blobs
+struct Loading {
+    items: Box<dyn Iterator<Item = anyhow::Result<Entry>>>,
+    /// Saved state to restore once every item has been loaded
+    view: Option<ViewState>,
 }
 
+/// Time spent loading items between redraws
+const LOAD_TIME: Duration = Duration::from_millis(20);
+
+/// Default width of the entry table, which fits all of its columns
+const TABLE_WIDTH: u16 = 45;
+/// Narrowest allowed entry table
+const MIN_TABLE_WIDTH: u16 = 24;
+/// Narrowest allowed data pane, when growing the entry table
+const MIN_DATA_WIDTH: u16 = 40;
+/// Shortest terminal in which the panes are drawn
+const MIN_HEIGHT: u16 = 10;
+/// Boundary on which memory map holes are expected to start and end; holes
+/// which don't are highlighted
+const HOLE_ALIGN: u64 = 1 << 30;
+
 pub struct App {
+    file: FileInfo,
     items: Vec<Entry>,
+    /// Indices into `items` for each visible row of the entry table
+    rows: Vec<usize>,
+    hide_cancelled: bool,
+    /// Whether the blob header and padding rows are hidden
+    hide_synthetic: bool,
+    sort: SortOrder,
+    /// Hex digits typed so far into the "go to offset" prompt, if active
+    goto_prompt: Option<String>,
+    /// Field spec typed so far into the struct overlay prompt, if active
+    overlay_prompt: Option<String>,
+    /// Fields to show entry data as, instead of a hex dump
+    overlay: Option<Spec>,
+    /// Decoders chosen by hand for item indices, overriding their types
+    decode_as: HashMap<usize, usize>,
+    /// Highlighted row of the "decode as" picker, if it's open
+    decoder_picker: Option<usize>,
+    show_help: bool,
+    /// Transient message shown in the footer until the next key press
+    status: Option<String>,
     item_state: TableState,
     data_state: TableState,
     data_scroll_cache: HashMap<usize, usize>,
     data_scroll_max: usize,
     data_width: usize,
     data_endian: Endian,
-    data_focus: bool,
+    focus: Focus,
+    /// Where the decoded view was last drawn, for focusing it with the mouse
+    specialized_area: Option<Rect>,
     data_grouping: DataGrouping,
+    data_interp: DataInterp,
+    data_text: TextEncoding,
     data_colors: bool,
+    /// Whether runs of all-zero rows are collapsed into one row
+    data_squeeze: bool,
+    /// Whether printable strings are listed below the data pane
+    show_strings: bool,
+    /// Whether the data pane includes the entry header
+    data_full: bool,
     specialized_state: Option<SpecializedState>,
     window_height: u16,
+    /// Width of the entry table, including its border
+    table_width: u16,
+    /// Whether the border between the panes is being dragged
+    dragging_divider: bool,
+    /// Indices of bookmarked items
+    bookmarks: BTreeSet<usize>,
+    decoders: Registry,
+    follow: Option<Follow>,
+    loading: Option<Loading>,
+    /// Key for checking entry HMACs, if one was given
+    #[cfg(feature = "hmac")]
+    hmac_key: Option<Vec<u8>>,
 }
 
 impl App {
-    pub fn new(items: Vec<Entry>) -> Self {
+    /// Builds a new viewer, restoring saved state if it matches `items`
+    pub fn new(
+        file: FileInfo,
+        items: Vec<Entry>,
+        decoders: Registry,
+        view: Option<ViewState>,
+    ) -> Self {
         let mut out = Self {
+            file,
             item_state: TableState::default().with_selected(0),
             data_state: TableState::default().with_selected(0),
             data_scroll_cache: HashMap::new(),
             data_scroll_max: 1,
             data_grouping: DataGrouping::Byte,
+            data_interp: DataInterp::Hex,
+            data_text: TextEncoding::Ascii,
             data_width: 8,
             data_endian: Endian::Little,
-            data_focus: false,
+            focus: Focus::Items,
+            specialized_area: None,
             data_colors: false,
+            data_squeeze: false,
+            show_strings: false,
+            data_full: false,
             specialized_state: None,
             window_height: 16,
+            table_width: TABLE_WIDTH,
+            dragging_divider: false,
+            bookmarks: BTreeSet::new(),
+            decoders,
+            follow: None,
+            loading: None,
+            #[cfg(feature = "hmac")]
+            hmac_key: None,
+            rows: (0..items.len()).collect(),
+            hide_cancelled: false,
+            hide_synthetic: false,
+            sort: SortOrder::Offset,
+            goto_prompt: None,
+            overlay_prompt: None,
+            overlay: None,
+            decode_as: HashMap::new(),
+            decoder_picker: None,
+            show_help: false,
+            status: None,
             items,
         };
-        out.set_item_scroll(0);
+        match view {
+            Some(v) if v.item_count == out.items.len() => out.restore(v),
+            _ => out.set_item_scroll(0),
+        }
         out
     }
 
-    pub fn run(mut self, mut terminal: ratatui::DefaultTerminal) {
+    fn restore(&mut self, v: ViewState) {
+        self.data_scroll_cache = v.data_scroll;
+        if matches!(v.data_width, 8 | 16) {
+            self.data_width = v.data_width;
+        }
+        if let Some(g) = DataGrouping::from_bytes(v.grouping) {
+            self.data_grouping = g;
+        }
+        self.data_endian = if v.big_endian {
+            Endian::Big
+        } else {
+            Endian::Little
+        };
+        self.data_colors = v.colors;
+        self.data_squeeze = v.squeeze;
+        self.bookmarks = v.bookmarks;
+        if v.table_width != 0 {
+            self.table_width = v.table_width.max(MIN_TABLE_WIDTH);
+        }
+        // The saved selection is an item index, which may be at a different
+        // row if the rows are sorted or filtered
+        self.set_item_scroll(self.row_of(v.selected));
+    }
+
+    fn view_state(&self) -> ViewState {
+        ViewState {
+            item_count: self.items.len(),
+            selected: self.selected().unwrap_or(0),
+            data_scroll: self.data_scroll_cache.clone(),
+            data_width: self.data_width,
+            grouping: self.data_grouping.bytes(),
+            big_endian: matches!(self.data_endian, Endian::Big),
+            colors: self.data_colors,
+            squeeze: self.data_squeeze,
+            table_width: self.table_width,
+            bookmarks: self.bookmarks.clone(),
+        }
+    }
+
+    /// Runs the viewer until the user quits, returning its final state
+    pub fn run(mut self, mut terminal: ratatui::DefaultTerminal) -> ViewState {
         ratatui::crossterm::execute!(
             std::io::stdout(),
             ratatui::crossterm::event::EnableMouseCapture
@@ -94,116 +693,234 @@ impl App {
         let mut scroll_momentum = 1;
         loop {
             terminal.draw(|frame| self.draw(frame)).unwrap();
+            // Don't wait for events while there's loading to do
+            let timeout = if self.loading.is_some() { 0 } else { 50 };
             let event_was_ready =
-                event::poll(std::time::Duration::from_millis(50))
+                event::poll(std::time::Duration::from_millis(timeout))
                     .unwrap_or(false);
+            if self.loading.is_some() {
+                self.load_more();
+                if !event_was_ready {
+                    continue;
+                }
+            }
+            // When following a file, wake up periodically to check it rather
+            // than blocking until the next event
+            if self.follow.is_some() {
+                self.check_follow();
+                if !event_was_ready {
+                    scroll_momentum = 1;
+                    continue;
+                }
+            }
             let e = event::read();
             // Use the mouse to set focus in one pane or the other
             if let Ok(Event::Mouse(m)) = &e {
-                self.data_focus = m.column > 45;
+                if !self.dragging_divider {
+                    let pos = ratatui::layout::Position::new(m.column, m.row);
+                    self.focus = if m.column < self.table_width {
+                        Focus::Items
+                    } else if self
+                        .specialized_area
+                        .is_some_and(|r| r.contains(pos))
+                    {
+                        Focus::Specialized
+                    } else {
+                        Focus::Data
+                    };
+                }
             }
             let mut reset_momentum = true;
             if !event_was_ready {
                 scroll_momentum = 1;
             }
+            if let Ok(Event::Key(..)) = &e {
+                self.status = None;
+            }
             match e {
+                Ok(Event::Key(key))
+                    if key.kind == KeyEventKind::Press
+                        && self.goto_prompt.is_some() =>
+                {
+                    self.goto_prompt_key(key.code)
+                }
+                Ok(Event::Key(key))
+                    if key.kind == KeyEventKind::Press
+                        && self.overlay_prompt.is_some() =>
+                {
+                    self.overlay_prompt_key(key.code)
+                }
+                Ok(Event::Key(key))
+                    if key.kind == KeyEventKind::Press
+                        && self.decoder_picker.is_some() =>
+                {
+                    self.decoder_picker_key(key.code)
+                }
+                Ok(Event::Key(key))
+                    if key.kind == KeyEventKind::Press && self.show_help =>
+                {
+                    if matches!(key.code, KeyCode::Char('?') | KeyCode::Esc) {
+                        self.show_help = false;
+                    }
+                }
                 Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
-                    match key.code {
-                        KeyCode::Char('0') => {
-                            if self.data_focus {
-                                self.set_data_scroll(0)
-                            } else {
-                                self.set_item_scroll(0)
+                    let action = BINDINGS
+                        .iter()
+                        .find(|b| b.keys.contains(&key.code))
+                        .map(|b| b.action);
+                    match action {
+                        Some(Action::Top) => self.first_row(),
+                        Some(Action::Group(g)) => {
+                            self.data_grouping = g;
+                            if g.bytes() < 4
+                                && self.data_interp == DataInterp::Float
+                            {
+                                self.data_interp = DataInterp::Hex;
                             }
                         }
-                        KeyCode::Char('1') => {
-                            self.data_grouping = DataGrouping::Byte
-                        }
-                        KeyCode::Char('2') => {
-                            self.data_grouping = DataGrouping::Word
-                        }
-                        KeyCode::Char('4') => {
-                            self.data_grouping = DataGrouping::DoubleWord
-                        }
-                        KeyCode::Char('8') => {
-                            self.data_grouping = DataGrouping::QuadWord
-                        }
-                        KeyCode::Char('e') => {
+                        Some(Action::Endian) => {
                             self.data_endian = match self.data_endian {
                                 Endian::Big => Endian::Little,
                                 Endian::Little => Endian::Big,
                             }
                         }
-                        KeyCode::Char('q') | KeyCode::Esc => break,
-                        KeyCode::Char('j') | KeyCode::Down => {
-                            if self.data_focus {
-                                self.next_data_row(1)
-                            } else {
-                                self.next_item_row(1)
+                        Some(Action::Signed) => {
+                            self.data_interp = match self.data_interp {
+                                DataInterp::Signed => DataInterp::Hex,
+                                _ => DataInterp::Signed,
                             }
                         }
-                        KeyCode::Char('k') | KeyCode::Up => {
-                            if self.data_focus {
-                                self.prev_data_row(1)
-                            } else {
-                                self.prev_item_row(1)
+                        // There's no float form of 1- or 2-byte groups
+                        Some(Action::Float)
+                            if self.data_grouping.bytes() >= 4 =>
+                        {
+                            self.data_interp = match self.data_interp {
+                                DataInterp::Float => DataInterp::Hex,
+                                _ => DataInterp::Float,
                             }
                         }
-                        KeyCode::Char('l') | KeyCode::Right => {
-                            self.data_focus = true;
-                        }
-                        KeyCode::Char('h') | KeyCode::Left => {
-                            self.data_focus = false;
+                        Some(Action::Text) => {
+                            self.data_text = self.data_text.next()
                         }
-                        KeyCode::Char('c') => {
+                        Some(Action::Quit) => break,
+                        Some(Action::Down) => self.next_row(1),
+                        Some(Action::Up) => self.prev_row(1),
+                        Some(Action::FocusData) => self.focus = Focus::Data,
+                        Some(Action::FocusItems) => self.focus = Focus::Items,
+                        Some(Action::CycleFocus) => self.cycle_focus(),
+                        Some(Action::Colors) => {
                             self.data_colors = !self.data_colors;
                         }
-                        KeyCode::PageDown => {
-                            if self.data_focus {
-                                self.next_data_row(self.window_height.into())
-                            } else {
-                                self.next_item_row(self.window_height.into())
+                        Some(Action::Squeeze) => self.relayout_data(|app| {
+                            app.data_squeeze = !app.data_squeeze
+                        }),
+                        Some(Action::Strings) => {
+                            self.show_strings = !self.show_strings;
+                        }
+                        Some(Action::FullEntry) => {
+                            self.data_full = !self.data_full;
+                            if let Some(row) = self.item_state.selected() {
+                                self.set_item_scroll(row);
                             }
                         }
-                        KeyCode::PageUp => {
-                            if self.data_focus {
-                                self.prev_data_row(self.window_height.into())
-                            } else {
-                                self.prev_item_row(self.window_height.into())
+                        Some(Action::StructOverlay) => {
+                            let spec = self.overlay.as_ref().map(Spec::text);
+                            self.overlay_prompt =
+                                Some(spec.unwrap_or_default().to_owned());
+                        }
+                        Some(Action::DecodeAs) => self.open_decoder_picker(),
+                        Some(Action::GotoOffset) => {
+                            self.goto_prompt = Some(String::new());
+                        }
+                        Some(Action::HideCancelled) => {
+                            self.hide_cancelled = !self.hide_cancelled;
+                            self.update_rows();
+                        }
+                        Some(Action::HideSynthetic) => {
+                            self.hide_synthetic = !self.hide_synthetic;
+                            self.update_rows();
+                        }
+                        Some(Action::Sort) => self.set_sort(match self.sort {
+                            SortOrder::Offset => SortOrder::GroupTypeInstance,
+                            SortOrder::Group | SortOrder::GroupTypeInstance => {
+                                SortOrder::Offset
                             }
+                        }),
+                        Some(Action::PageDown) => {
+                            self.next_row(self.window_height.into())
+                        }
+                        Some(Action::PageUp) => {
+                            self.prev_row(self.window_height.into())
+                        }
+                        Some(Action::NextInGroup) => self.jump_in_group(true),
+                        Some(Action::PrevInGroup) => self.jump_in_group(false),
+                        Some(Action::Bookmark) => self.toggle_bookmark(),
+                        Some(Action::NextBookmark) => {
+                            self.jump_to_bookmark(true)
+                        }
+                        Some(Action::PrevBookmark) => {
+                            self.jump_to_bookmark(false)
                         }
-                        _ => (),
+                        Some(Action::CopyRaw) => self.copy_raw(),
+                        Some(Action::CopyDecoded) => self.copy_decoded(),
+                        Some(Action::WriteView) => self.write_view(),
+                        Some(Action::ShrinkTable) => {
+                            self.table_width = self
+                                .table_width
+                                .saturating_sub(2)
+                                .max(MIN_TABLE_WIDTH);
+                        }
+                        // The upper bound depends on the terminal width, so
+                        // it's applied in `draw`
+                        Some(Action::GrowTable) => self.table_width += 2,
+                        Some(Action::Help) => self.show_help = true,
+                        Some(Action::Float) | None => (),
                     }
                 }
+                // Either side of the border between the panes grabs it
+                Ok(Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column,
+                    ..
+                })) if column + 1 == self.table_width
+                    || column == self.table_width =>
+                {
+                    self.dragging_divider = true;
+                }
+                Ok(Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Drag(MouseButton::Left),
+                    column,
+                    ..
+                })) if self.dragging_divider => {
+                    self.table_width = (column + 1).max(MIN_TABLE_WIDTH);
+                }
+                Ok(Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Up(MouseButton::Left),
+                    ..
+                })) if self.dragging_divider => {
+                    self.dragging_divider = false;
+                }
                 Ok(Event::Mouse(MouseEvent {
                     kind: MouseEventKind::Down(MouseButton::Left),
                     row,
                     ..
-                })) if !self.data_focus => {
+                })) if self.focus == Focus::Items => {
                     let i = self.item_state.offset();
-                    if let Some(sel) = (i + usize::from(row)).checked_sub(2) {
-                        if sel < self.items.len() {
+                    if let Some(sel) = (i + usize::from(row)).checked_sub(3) {
+                        if sel < self.rows.len() {
                             self.set_item_scroll(sel);
                         }
                     }
                 }
                 Ok(Event::Mouse(m)) if m.kind == MouseEventKind::ScrollDown => {
-                    if self.data_focus {
-                        self.next_data_row(scroll_momentum)
-                    } else {
-                        self.next_item_row(scroll_momentum)
-                    }
+                    self.next_row(scroll_momentum);
                     if event_was_ready {
                         reset_momentum = false;
                         scroll_momentum = (scroll_momentum + 1).min(16);
                     }
                 }
                 Ok(Event::Mouse(m)) if m.kind == MouseEventKind::ScrollUp => {
-                    if self.data_focus {
-                        self.prev_data_row(scroll_momentum)
-                    } else {
-                        self.prev_item_row(scroll_momentum)
-                    }
+                    self.prev_row(scroll_momentum);
                     if event_was_ready {
                         reset_momentum = false;
                         scroll_momentum = (scroll_momentum + 1).min(16);
@@ -221,44 +938,103 @@ impl App {
             ratatui::crossterm::event::DisableMouseCapture
         )
         .unwrap();
+        self.view_state()
+    }
+
+    /// Decodes an item, using the decoder picked for it if there is one
+    fn decode(&self, i: usize) -> Option<DecodedSection<'_>> {
+        Self::decode_item(&self.decoders, &self.decode_as, i, &self.items[i])
+    }
+
+    /// Implements [`App::decode`], borrowing only the fields it needs
+    fn decode_item<'a>(
+        decoders: &Registry,
+        decode_as: &HashMap<usize, usize>,
+        i: usize,
+        item: &'a Entry,
+    ) -> Option<DecodedSection<'a>> {
+        let Item::Entry(e) = &item.entry else {
+            return None;
+        };
+        match decode_as.get(&i) {
+            Some(&d) => decoders.decode_as(d, item.data()),
+            None => decoders.decode(e, item.data()),
+        }
     }
 
     /// Checks whether we have a specialized drawing algorithm for this entry
-    fn specialized(i: Item) -> Option<SpecializedTag> {
-        match i {
-            Item::Entry(h) => match (h.group(), h.ty) {
-                (Some(apob::ApobGroup::GENERAL), 6) => {
-                    Some(SpecializedTag::EventLog)
-                }
-                (Some(apob::ApobGroup::FABRIC), t)
-                    if t == apob::ApobFabricType::SYS_MEM_MAP as u32 =>
-                {
-                    Some(SpecializedTag::MemMap)
+    fn specialized(&self, i: usize) -> Option<SpecializedTag> {
+        if self.show_strings {
+            return Some(SpecializedTag::Strings);
+        }
+        match &self.items[i].entry {
+            Item::Entry(..) => match self.decode(i)? {
+                DecodedSection::EventLog(..) => Some(SpecializedTag::EventLog),
+                DecodedSection::MemMap { .. } => Some(SpecializedTag::MemMap),
+                DecodedSection::PmuTrainFail(..) => {
+                    Some(SpecializedTag::PmuTrainingFailure)
                 }
-                (Some(apob::ApobGroup::MEMORY), t)
-                    if t == apob::ApobMemoryType::MILAN_PMU_TRAIN_FAIL
-                        as u32 =>
+                DecodedSection::Coremap(..) => Some(SpecializedTag::Coremap),
+                DecodedSection::Smbios(..) => Some(SpecializedTag::Smbios),
+                // Otherwise there would be no sign of a forced decode, or of
+                // why it failed
+                DecodedSection::PhyOverride(..)
+                | DecodedSection::Apcb { .. }
+                | DecodedSection::Invalid(..)
+                    if self.decode_as.contains_key(&i) =>
                 {
-                    Some(SpecializedTag::PmuTrainingFailure)
+                    Some(SpecializedTag::Text)
                 }
-                _ => None,
+                DecodedSection::PhyOverride(..)
+                | DecodedSection::Apcb { .. }
+                | DecodedSection::Invalid(..) => None,
             },
             Item::Header(_) => Some(SpecializedTag::Header),
-            _ => None,
+            Item::Padding | Item::Trailing | Item::Truncated(..) => None,
         }
     }
 
     fn draw(&mut self, frame: &mut Frame) {
-        let cols =
-            &Layout::horizontal([Constraint::Length(45), Constraint::Fill(1)]);
-        let rects = cols.split(frame.area());
+        let full = frame.area();
+        if full.width < MIN_TABLE_WIDTH + MIN_DATA_WIDTH
+            || full.height < MIN_HEIGHT
+        {
+            let msg = format!(
+                "terminal too small: {}x{}, need at least {}x{}",
+                full.width,
+                full.height,
+                MIN_TABLE_WIDTH + MIN_DATA_WIDTH,
+                MIN_HEIGHT
+            );
+            frame.render_widget(
+                Paragraph::new(msg).wrap(Wrap { trim: true }),
+                full,
+            );
+            return;
+        }
+        let max_width = frame
+            .area()
+            .width
+            .saturating_sub(MIN_DATA_WIDTH)
+            .max(MIN_TABLE_WIDTH);
+        self.table_width = self.table_width.min(max_width);
+        let cols = &Layout::horizontal([
+            Constraint::Length(self.table_width),
+            Constraint::Fill(1),
+        ]);
+        let [title, area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)])
+                .areas(frame.area());
+        self.render_title(frame, title);
+        let rects = cols.split(area);
         self.window_height = rects[0].height.saturating_sub(3);
-        self.render_table(frame, rects[0], !self.data_focus);
+        self.render_table(frame, rects[0], self.focus == Focus::Items);
 
-        let specialized = self
-            .item_state
-            .selected()
-            .and_then(|i| Self::specialized(self.items[i].entry));
+        let specialized = self.selected().and_then(|i| self.specialized(i));
+        // The selected item may have changed to one without a decoded view
+        if specialized.is_none() && self.focus == Focus::Specialized {
+            self.focus = Focus::Data;
+        }
 
         let rows = if specialized.is_some() {
             Layout::vertical([
@@ -270,24 +1046,193 @@ impl App {
             Layout::vertical([Constraint::Fill(1), Constraint::Length(1)])
         };
         let rects = rows.split(rects[1]);
-        self.render_data(frame, rects[0], self.data_focus);
+        self.render_data(frame, rects[0], self.focus == Focus::Data);
 
         if let Some(s) = specialized {
-            self.render_specialized(s, frame, rects[1]);
+            let focus = self.focus == Focus::Specialized;
+            self.render_specialized(s, frame, rects[1], focus);
+            self.specialized_area = Some(rects[1]);
         } else {
             self.clear_specialized();
+            self.specialized_area = None;
         }
 
         let help = Span::raw(format!(
-            " [{}]-byte groups, [c]olor {}, {}-[e]ndian",
+            " [?] help, [{}]-byte groups, {} [s/f], [t]ext {}, [c]olor {}, \
+             {}-[e]ndian, cancelled [x] {}",
             self.data_grouping.bytes(),
+            match self.data_interp {
+                DataInterp::Hex => "hex",
+                DataInterp::Signed => "signed",
+                DataInterp::Float => "float",
+            },
+            self.data_text.name(),
             if self.data_colors { "on" } else { "off" },
             match self.data_endian {
                 Endian::Big => "big",
                 Endian::Little => "little",
             },
+            if self.hide_cancelled {
+                "hidden"
+            } else {
+                "shown"
+            },
         ));
-        frame.render_widget(help, *rects.last().unwrap());
+        let footer = if let Some(p) = &self.goto_prompt {
+            Span::raw(format!(" go to offset: 0x{p}_"))
+        } else if let Some(p) = &self.overlay_prompt {
+            Span::raw(format!(" fields: {p}_"))
+        } else if let Some(s) = &self.status {
+            Span::raw(format!(" {s}"))
+        } else {
+            help
+        };
+        frame.render_widget(footer, *rects.last().unwrap());
+
+        if self.show_help {
+            Self::render_help(frame);
+        }
+        if let Some(row) = self.decoder_picker {
+            self.render_decoder_picker(frame, row);
+        }
+    }
+
+    /// Draws the list of decoders for "decode as", centered in the frame
+    fn render_decoder_picker(&self, frame: &mut Frame, row: usize) {
+        let lines = std::iter::once("Entry's own type")
+            .chain(self.decoders.names())
+            .enumerate()
+            .map(|(j, name)| {
+                let line = Line::raw(format!(" {name} "));
+                if j == row {
+                    line.style(Style::new().add_modifier(Modifier::REVERSED))
+                } else {
+                    line
+                }
+            })
+            .collect::<Vec<_>>();
+        let width = lines.iter().map(|l| l.width()).max().unwrap_or(0) + 2;
+        let height = lines.len() + 2;
+        let [rect] = Layout::horizontal([Constraint::Length(width as u16)])
+            .flex(Flex::Center)
+            .areas(frame.area());
+        let [rect] = Layout::vertical([Constraint::Length(height as u16)])
+            .flex(Flex::Center)
+            .areas(rect);
+        frame.render_widget(Clear, rect);
+        frame.render_widget(
+            Paragraph::new(Text::from(lines)).block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title("Decode as")
+                    .title_style(Style::reset().add_modifier(Modifier::BOLD)),
+            ),
+            rect,
+        );
+    }
+
+    fn render_title(&self, frame: &mut Frame, area: Rect) {
+        let mut version = None;
+        let mut entries = 0;
+        let mut cancelled = 0;
+        for item in &self.items {
+            match &item.entry {
+                Item::Header(h) => version = Some(h.version),
+                Item::Entry(e) => {
+                    entries += 1;
+                    cancelled += usize::from(e.cancelled());
+                }
+                Item::Padding | Item::Trailing | Item::Truncated(..) => (),
+            }
+        }
+        let mut parts = vec![
+            self.file.name.clone(),
+            format!("{:#x} bytes", self.file.size),
+        ];
+        match version {
+            Some(v) if v != apob::APOB_VERSION => {
+                parts.push(format!("UNSUPPORTED version {v:#x}"));
+            }
+            Some(v) => parts.push(format!("version {v:#x}")),
+            None => (),
+        }
+        parts.push(format!("{entries} entries ({cancelled} cancelled)"));
+        if self.follow.is_some() {
+            parts.push("following".to_owned());
+        }
+        if self.loading.is_some() {
+            parts.push("loading…".to_owned());
+        }
+        match self.sort {
+            SortOrder::Offset => (),
+            SortOrder::Group => parts.push("sorted by group".to_owned()),
+            SortOrder::GroupTypeInstance => {
+                parts.push("sorted by group/type/instance".to_owned())
+            }
+        }
+        let text = format!(" {}", parts.join(" | "));
+        frame.render_widget(
+            Line::from(text)
+                .style(Style::new().add_modifier(Modifier::REVERSED)),
+            area,
+        );
+    }
+
+    fn render_help(frame: &mut Frame) {
+        let area = frame.area();
+        frame
+            .buffer_mut()
+            .set_style(area, Style::new().add_modifier(Modifier::DIM));
+
+        let label_width = BINDINGS.iter().map(|b| b.label.len()).max();
+        let label_width = label_width.unwrap_or(0);
+        let lines = BINDINGS
+            .iter()
+            .map(|b| {
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {:<label_width$}  ", b.label),
+                        Style::new().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(b.help),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        // Legend for the entry table, with a few groups on each line
+        let mut lines = lines;
+        lines.push(Line::default());
+        let groups = apob::ApobGroup::iter().collect::<Vec<_>>();
+        for chunk in groups.chunks(5) {
+            let mut spans = vec![Span::raw(" ")];
+            for &g in chunk {
+                spans.push(Span::styled(
+                    "██",
+                    Style::new().fg(crate::color::group(g)),
+                ));
+                spans.push(Span::raw(format!(" {:<10}", apob::group_name(g))));
+            }
+            lines.push(Line::from(spans));
+        }
+
+        let width = lines.iter().map(|l| l.width()).max().unwrap_or(0) + 3;
+        let height = lines.len() + 2;
+        let [rect] = Layout::horizontal([Constraint::Length(width as u16)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [rect] = Layout::vertical([Constraint::Length(height as u16)])
+            .flex(Flex::Center)
+            .areas(rect);
+        frame.render_widget(Clear, rect);
+        frame.render_widget(
+            Paragraph::new(Text::from(lines)).block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title("Keys")
+                    .title_style(Style::reset().add_modifier(Modifier::BOLD)),
+            ),
+            rect,
+        );
     }
 
     fn render_specialized(
@@ -295,11 +1240,19 @@ impl App {
         s: SpecializedTag,
         frame: &mut Frame,
         rect: Rect,
+        focus: bool,
     ) {
         let needs_reset =
             self.specialized_state.as_ref().map(SpecializedTag::from)
                 != Some(s);
-        let entry = &self.items[self.item_state.selected().unwrap()];
+        let i = self.selected().unwrap();
+        let entry = &self.items[i];
+        let section =
+            Self::decode_item(&self.decoders, &self.decode_as, i, entry);
+        let title = match self.decode_as.get(&i) {
+            Some(&d) => self.decoders.names().nth(d),
+            None => None,
+        };
         if needs_reset {
             self.specialized_state = Some(match s {
                 SpecializedTag::MemMap => {
@@ -311,36 +1264,124 @@ impl App {
                 SpecializedTag::PmuTrainingFailure => {
                     SpecializedState::PmuTrainingFailure(TableState::new())
                 }
+                SpecializedTag::Coremap => {
+                    SpecializedState::Coremap(TableState::new())
+                }
+                SpecializedTag::Smbios => {
+                    SpecializedState::Smbios(TableState::new())
+                }
                 SpecializedTag::Header => SpecializedState::Header,
+                SpecializedTag::Text => SpecializedState::Text,
+                SpecializedTag::Strings => {
+                    SpecializedState::Strings(TableState::new())
+                }
             })
         }
 
+        // Focusing the view selects its first row
+        if focus {
+            let state = self.specialized_state.as_mut();
+            if let Some(t) = state.and_then(SpecializedState::table) {
+                if t.selected().is_none() {
+                    t.select(Some(0));
+                }
+            }
+        }
+
         let header_style = Style::default().add_modifier(Modifier::BOLD);
+        let border_style = Self::border_style(focus);
         let selected_row_style = Style::new().add_modifier(Modifier::REVERSED);
 
         let cf = |t| Cell::from(Span::from(t));
         let cfr = |t| Cell::from(Line::from(t).alignment(Alignment::Right));
 
-        match self.specialized_state.as_mut().unwrap() {
-            SpecializedState::MemMap(data) => {
+        match (self.specialized_state.as_mut().unwrap(), section) {
+            (SpecializedState::Strings(data), _) => {
+                let header = ["OFFSET", "TEXT"]
+                    .into_iter()
+                    .map(Cell::from)
+                    .collect::<Row>()
+                    .style(header_style);
+                // Same bytes as `shown_data`, which would borrow all of `self`
+                let shown = if self.data_full {
+                    entry.bytes()
+                } else {
+                    entry.data()
+                };
+                let strings = crate::decode::extract_strings(
+                    shown,
+                    crate::decode::MIN_STRING_LEN,
+                );
+                let mut rows = strings
+                    .into_iter()
+                    .map(|(offset, s)| {
+                        Row::new([
+                            Cell::from(
+                                Line::from(format!("{offset:06x}")).style(
+                                    Style::new().add_modifier(Modifier::DIM),
+                                ),
+                            ),
+                            Cell::from(s),
+                        ])
+                    })
+                    .collect::<Vec<_>>();
+                if rows.is_empty() {
+                    rows.push(std::iter::once(Cell::from(" --")).collect());
+                }
+                Self::clamp_selection(data, rows.len());
+                let t = Table::new(
+                    rows,
+                    [Constraint::Length(8), Constraint::Fill(1)],
+                )
+                .header(header)
+                .row_highlight_style(selected_row_style)
+                .block(
+                    Block::new()
+                        .borders(Borders::ALL)
+                        .border_style(border_style)
+                        .title(format!(
+                            "Strings of {} or more characters",
+                            crate::decode::MIN_STRING_LEN
+                        ))
+                        .title_style(header_style),
+                );
+                frame.render_stateful_widget(t, rect, data);
+            }
+            (
+                SpecializedState::MemMap(data),
+                Some(DecodedSection::MemMap {
+                    high_phys,
+                    usable,
+                    holes,
+                }),
+            ) => {
                 let header = ["BASE", "SIZE", "TYPE"]
                     .into_iter()
                     .map(Cell::from)
                     .collect::<Row>()
                     .style(header_style);
-                let (map, holes) =
-                    apob::ApobSysMemMap::ref_from_prefix(&entry.data).unwrap();
-                let holes =
-                    <[apob::ApobSysMemMapHole]>::ref_from_bytes(holes).unwrap();
-
-                let holes = holes[..map.hole_count as usize].iter().map(|h| {
+                let holes = holes.iter().map(|h| {
+                    let (base, size) = (h.base, h.size);
+                    let end = base.wrapping_add(size);
+                    // An empty hole is probably a bogus record, so it
+                    // outranks misalignment.  `u64::is_multiple_of` is newer
+                    // than our minimum supported Rust version.
+                    #[allow(unknown_lints, clippy::manual_is_multiple_of)]
+                    let check = |v: u64| {
+                        if size == 0 {
+                            Style::new().fg(Color::Red)
+                        } else if v % HOLE_ALIGN != 0 {
+                            Style::new().fg(Color::Yellow)
+                        } else {
+                            Style::new()
+                        }
+                    };
                     [
-                        format!("0x{:0>10x}", h.base),
-                        format!("0x{:0>8x}", h.size),
-                        format!("{:#04x}", h.ty),
+                        cf(format!("0x{base:0>10x}")).style(check(base)),
+                        cf(format!("0x{size:0>8x}")).style(check(end)),
+                        cf(format!("{:#04x}", { h.ty })).style(check(0)),
                     ]
                     .into_iter()
-                    .map(Cell::from)
                     .collect::<Row>()
                 });
 
@@ -351,19 +1392,24 @@ impl App {
                 };
                 let outer = Block::new()
                     .borders(Borders::ALL)
+                    .border_style(border_style)
                     .title("APOB memory map")
-                    .title_style(header_style); // TODO focus?
+                    .title_style(header_style);
                 frame.render_widget(outer, rect);
 
                 let header_rect = rect.inner(Margin::new(1, 1));
                 frame.render_widget(
-                    Span::from(format!("high_phys: {:#x}", map.high_phys)),
+                    Span::from(format!(
+                        "high_phys: {high_phys:#x}   usable: {}",
+                        crate::human_size(usable)
+                    )),
                     header_rect,
                 );
 
                 let mut rect = rect;
                 rect.y += 3;
-                rect.height -= 3;
+                rect.height = rect.height.saturating_sub(3);
+                Self::clamp_selection(data, holes.len());
                 let t = Table::new(
                     holes,
                     [
@@ -378,58 +1424,47 @@ impl App {
                     Block::new()
                         .borders(Borders::ALL)
                         .border_set(border_set)
+                        .border_style(border_style)
                         .title("HOLES")
                         .title_style(header_style),
                 );
 
                 frame.render_stateful_widget(t, rect, data);
             }
-            SpecializedState::EventLog(data) => {
+            (
+                SpecializedState::EventLog(data),
+                Some(DecodedSection::EventLog(events)),
+            ) => {
                 let header = ["INDEX", " CLASS", "EVENT", "DATA", ""]
                     .into_iter()
                     .map(Cell::from)
                     .collect::<Row>()
                     .style(header_style);
-                let (log, _) =
-                    apob::MilanApobEventLog::ref_from_prefix(&entry.data)
-                        .unwrap();
                 let mut data0_len = 0;
                 let mut data1_len = 0;
                 let mut rows = vec![];
-                for (i, v) in
-                    log.events[..log.count as usize].iter().enumerate()
-                {
-                    let class =
-                        apob::MilanApobEventClass::from_repr(v.class as usize);
-                    let class_color = class.map(|c| match c {
-                        apob::MilanApobEventClass::ALERT => Color::Green,
-                        apob::MilanApobEventClass::WARN => Color::Blue,
-                        apob::MilanApobEventClass::ERROR => Color::Magenta,
-                        apob::MilanApobEventClass::CRIT => Color::Yellow,
-                        apob::MilanApobEventClass::FATAL => Color::Red,
-                    });
-                    let info =
-                        apob::MilanApobEventInfo::from_repr(v.info as usize);
-                    let data0 = format!("{:#x}", v.data0);
-                    let data1 = format!("{:#x}", v.data1);
+                for (i, v) in events.iter().enumerate() {
+                    let class = v.class();
+                    let class_color = class.map(crate::color::event_class);
+                    let info = apob::event_info_name(v.info);
+                    let data0 = format!("{:#x}", { v.data0 });
+                    let data1 = format!("{:#x}", { v.data1 });
                     data0_len = data0_len.max(data0.len());
                     data1_len = data1_len.max(data1.len());
                     let row = [
                         cfr(format!("{i:02x}")),
                         if let Some(c) = class {
-                            cf(format!(
-                                " {:<5} ({:#x})",
-                                format!("{c:?}"),
+                            cf(format!(" {:<5} ({:#x})", format!("{c:?}"), {
                                 v.class
-                            ))
+                            }))
                             .style(Style::new().fg(class_color.unwrap()))
                         } else {
-                            cf(format!(" {:#x}", v.class))
+                            cf(format!(" {:#x}", { v.class }))
                         },
                         if let Some(i) = info {
-                            cf(format!("{i:?} ({:#x})", v.info))
+                            cf(format!("{i} ({:#x})", { v.info }))
                         } else {
-                            cf(format!("{:#x}", v.info))
+                            cf(format!("{:#x}", { v.info }))
                         },
                         cf(data0),
                         cf(data1),
@@ -450,31 +1485,14 @@ impl App {
                         .collect::<Row>();
                         rows.push(row)
                     };
-                    if matches!(
-                        info,
-                        Some(apob::MilanApobEventInfo::TRAIN_ERROR)
-                    ) {
-                        let data0 = apob::MilanTrainErrorData0(v.data0);
-                        push_bonus_event(format!(
-                            "  sock: {}  chan: {}",
-                            data0.sock(),
-                            data0.chan()
-                        ));
-                        push_bonus_event(format!(
-                            "  dimm: {}  rank: {}",
-                            data0.dimm(),
-                            data0.rank()
-                        ));
-                        let data1 = apob::MilanTrainErrorData1(v.data1);
-                        if data1.pmu_load() {
-                            push_bonus_event("  PMU load error".to_string());
-                        }
-                        if data1.pmu_train() {
-                            push_bonus_event("  PMU train error".to_string());
-                        }
+                    for line in
+                        apob::decode_event_data(v.info, v.data0, v.data1)
+                    {
+                        push_bonus_event(format!("  {line}"));
                     }
                 }
 
+                Self::clamp_selection(data, rows.len());
                 let t = Table::new(
                     rows,
                     [
@@ -490,14 +1508,29 @@ impl App {
                 .block(
                     Block::new()
                         .borders(Borders::ALL)
-                        .title("APOB event log")
-                        .title_style(header_style), // TODO focus?
+                        .title("Milan APOB event log")
+                        .border_style(border_style)
+                        .title_style(header_style),
                 );
 
                 frame.render_stateful_widget(t, rect, data);
             }
-            SpecializedState::Header => {
-                let Item::Header(h) = entry.entry else {
+            (SpecializedState::Text, Some(section)) => {
+                let mut out = vec![];
+                crate::write_section(&mut out, section, false, None, false)
+                    .unwrap();
+                let text = String::from_utf8_lossy(&out).into_owned();
+                // Errors can be long, so wrap them rather than cutting them off
+                let b = Paragraph::new(text).wrap(Wrap { trim: false }).block(
+                    Block::new()
+                        .borders(Borders::ALL)
+                        .title(format!("Decoded as {}", title.unwrap_or("?")))
+                        .title_style(header_style),
+                );
+                frame.render_widget(b, rect);
+            }
+            (SpecializedState::Header, _) => {
+                let Item::Header(h) = entry.entry else {
                     panic!();
                 };
                 let sig = if let Ok(s) = std::str::from_utf8(h.sig.as_slice()) {
@@ -507,9 +1540,9 @@ impl App {
                 };
                 let lines = vec![
                     Line::raw(format!("signature: {sig}")),
-                    Line::raw(format!("version:    {:#x}", h.version)),
-                    Line::raw(format!("size:       {:#x}", h.size)),
-                    Line::raw(format!("offset:     {:#x}", h.offset)),
+                    Line::raw(format!("version:    {:#x}", { h.version })),
+                    Line::raw(format!("size:       {:#x}", { h.size })),
+                    Line::raw(format!("offset:     {:#x}", { h.offset })),
                 ];
                 let b = Paragraph::new(Text::from(lines)).block(
                     Block::new()
@@ -519,7 +1552,10 @@ impl App {
                 );
                 frame.render_widget(b, rect);
             }
-            SpecializedState::PmuTrainingFailure(data) => {
+            (
+                SpecializedState::PmuTrainingFailure(data),
+                Some(DecodedSection::PmuTrainFail(entries)),
+            ) => {
                 let header = [
                     "INDEX", "SOCK", "UMC", "1D2D", "1DNUM", "STAGE", "ERROR",
                     "DATA", "", "", "",
@@ -528,16 +1564,14 @@ impl App {
                 .map(Cell::from)
                 .collect::<Row>()
                 .style(header_style);
-                let (tfi, _) =
-                    apob::PmuTfi::ref_from_prefix(&entry.data).unwrap();
                 let mut data_len = [0usize; 4];
                 let mut err_len = 0usize;
-                let mut log = tfi.entries[..tfi.nvalid as usize]
+                let mut log = entries
                     .iter()
                     .enumerate()
                     .map(|(i, v)| {
                         let mut data_fmt = v.data.map(|d| format!("{d:#x}"));
-                        let err_fmt = format!("{:#x}", v.error);
+                        let err_fmt = format!("{:#x}", { v.error });
                         err_len = err_len.max(err_fmt.len());
                         for (n, d) in data_len.iter_mut().zip(&data_fmt) {
                             *n = (*n).max(d.len());
@@ -563,6 +1597,7 @@ impl App {
                     log.push(std::iter::once(Cell::from(" --")).collect());
                 }
 
+                Self::clamp_selection(data, log.len());
                 let t = Table::new(
                     log,
                     [
@@ -585,11 +1620,138 @@ impl App {
                     Block::new()
                         .borders(Borders::ALL)
                         .title("PMU training failure log")
-                        .title_style(header_style), // TODO focus?
+                        .border_style(border_style)
+                        .title_style(header_style),
                 );
 
                 frame.render_stateful_widget(t, rect, data);
             }
+            (
+                SpecializedState::Coremap(data),
+                Some(DecodedSection::Coremap(map)),
+            ) => {
+                let header = ["CCD", "CCX", "CORE", "THREADS"]
+                    .into_iter()
+                    .map(Cell::from)
+                    .collect::<Row>()
+                    .style(header_style);
+                let ccx_style =
+                    Style::new().fg(crate::color::group(apob::ApobGroup::CCX));
+                let mut prev = None;
+                let mut rows = map
+                    .present_cores()
+                    .map(|(ccd, ccx, core)| {
+                        // Only print CCD and CCX IDs when they change, so
+                        // that the table reads as a tree
+                        let ids = (ccd.macd_id, ccx.macx_id);
+                        let (ccd_id, ccx_id) = match prev.replace(ids) {
+                            Some((d, x)) if d == ids.0 && x == ids.1 => {
+                                (String::new(), String::new())
+                            }
+                            Some((d, _)) if d == ids.0 => {
+                                (String::new(), format!("{:#04x}", ids.1))
+                            }
+                            _ => (
+                                format!("{:#04x}", ids.0),
+                                format!("{:#04x}", ids.1),
+                            ),
+                        };
+                        let threads = core
+                            .threads()
+                            .map(|t| t.to_string())
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        [
+                            cf(ccd_id).style(ccx_style),
+                            cf(ccx_id).style(ccx_style),
+                            cf(format!("{:#04x}", core.mac_id)),
+                            cf(threads),
+                        ]
+                        .into_iter()
+                        .collect::<Row>()
+                    })
+                    .collect::<Vec<_>>();
+                if rows.is_empty() {
+                    rows.push(std::iter::once(Cell::from(" --")).collect());
+                }
+
+                Self::clamp_selection(data, rows.len());
+                let t = Table::new(
+                    rows,
+                    [
+                        Constraint::Length(4),
+                        Constraint::Length(4),
+                        Constraint::Length(4),
+                        Constraint::Length(7),
+                    ],
+                )
+                .header(header)
+                .row_highlight_style(selected_row_style)
+                .block(
+                    Block::new()
+                        .borders(Borders::ALL)
+                        .title("CCX core map")
+                        .border_style(border_style)
+                        .title_style(header_style),
+                );
+
+                frame.render_stateful_widget(t, rect, data);
+            }
+            (
+                SpecializedState::Smbios(data),
+                Some(DecodedSection::Smbios(structures)),
+            ) => {
+                let header = ["HANDLE", "TYPE", "LENGTH", "STRINGS"]
+                    .into_iter()
+                    .map(Cell::from)
+                    .collect::<Row>()
+                    .style(header_style);
+                let mut rows = structures
+                    .iter()
+                    .map(|s| {
+                        let strings = s
+                            .strings()
+                            .map(|t| format!("\"{}\"", t.escape_ascii()))
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        [
+                            cf(format!("{:#06x}", { s.header.handle })),
+                            cfr(s.header.ty.to_string()),
+                            cfr(format!("{:#x}", s.header.length)),
+                            cf(strings),
+                        ]
+                        .into_iter()
+                        .collect::<Row>()
+                    })
+                    .collect::<Vec<_>>();
+                if rows.is_empty() {
+                    rows.push(std::iter::once(Cell::from(" --")).collect());
+                }
+
+                Self::clamp_selection(data, rows.len());
+                let t = Table::new(
+                    rows,
+                    [
+                        Constraint::Length(6),
+                        Constraint::Length(4),
+                        Constraint::Length(6),
+                        Constraint::Fill(1),
+                    ],
+                )
+                .header(header)
+                .row_highlight_style(selected_row_style)
+                .block(
+                    Block::new()
+                        .borders(Borders::ALL)
+                        .title("SMBIOS structures")
+                        .border_style(border_style)
+                        .title_style(header_style),
+                );
+
+                frame.render_stateful_widget(t, rect, data);
+            }
+            // `specialized` only picks a state matching the decoded section
+            _ => unreachable!(),
         };
     }
 
@@ -597,18 +1759,132 @@ impl App {
         self.specialized_state = None;
     }
 
+    /// Returns the state of the decoded view's table, if it has one
+    fn specialized_table(&mut self) -> Option<&mut TableState> {
+        self.specialized_state
+            .as_mut()
+            .and_then(SpecializedState::table)
+    }
+
+    /// Keeps a table's selection within its `n` rows
+    ///
+    /// Moving down doesn't know how many rows there are, so it may overshoot.
+    fn clamp_selection(state: &mut TableState, n: usize) {
+        if let Some(i) = state.selected() {
+            state.select(Some(i.min(n.saturating_sub(1))));
+        }
+    }
+
+    /// Moves focus to the next pane, skipping the decoded view if it has no
+    /// table
+    fn cycle_focus(&mut self) {
+        let has_table = self.specialized_table().is_some();
+        self.focus = match self.focus {
+            Focus::Items => Focus::Data,
+            Focus::Data if has_table => Focus::Specialized,
+            Focus::Data | Focus::Specialized => Focus::Items,
+        };
+    }
+
+    /// Moves down `d` rows in the focused pane
+    fn next_row(&mut self, d: usize) {
+        match self.focus {
+            Focus::Items => self.next_item_row(d),
+            Focus::Data => self.next_data_row(d),
+            Focus::Specialized => {
+                if let Some(t) = self.specialized_table() {
+                    t.scroll_down_by(d.try_into().unwrap_or(u16::MAX));
+                }
+            }
+        }
+    }
+
+    /// Moves up `d` rows in the focused pane
+    fn prev_row(&mut self, d: usize) {
+        match self.focus {
+            Focus::Items => self.prev_item_row(d),
+            Focus::Data => self.prev_data_row(d),
+            Focus::Specialized => {
+                if let Some(t) = self.specialized_table() {
+                    t.scroll_up_by(d.try_into().unwrap_or(u16::MAX));
+                }
+            }
+        }
+    }
+
+    /// Moves to the first row of the focused pane
+    fn first_row(&mut self) {
+        match self.focus {
+            Focus::Items => self.set_item_scroll(0),
+            Focus::Data => self.set_data_scroll(0),
+            Focus::Specialized => {
+                if let Some(t) = self.specialized_table() {
+                    t.select_first();
+                }
+            }
+        }
+    }
+
     fn resize_data(&mut self, data_width: usize) {
         if data_width != self.data_width {
-            for (_, row) in self.data_scroll_cache.iter_mut() {
-                let index = *row * self.data_width;
-                *row = index / data_width;
-            }
-            if let Some(row) = self.data_state.selected() {
-                let index = row * self.data_width;
-                self.set_data_scroll(index / data_width);
+            self.relayout_data(|app| app.data_width = data_width);
+        }
+    }
+
+    /// Changes how the hex dump is split into rows, keeping each item's data
+    /// pane scrolled to the same bytes
+    fn relayout_data(&mut self, f: impl FnOnce(&mut Self)) {
+        if let Some((i, row)) = self.selected().zip(self.data_state.selected())
+        {
+            self.data_scroll_cache.insert(i, row);
+        }
+        let offsets = self
+            .data_scroll_cache
+            .iter()
+            .map(|(&i, &row)| (i, self.row_offset(i, row)))
+            .collect::<Vec<_>>();
+        f(self);
+        for (i, offset) in offsets {
+            let row = self.offset_row(i, offset);
+            self.data_scroll_cache.insert(i, row);
+        }
+        if let Some(row) = self.item_state.selected() {
+            self.set_item_scroll(row);
+        }
+    }
+
+    /// Returns the range of shown bytes on each row of the hex dump
+    ///
+    /// When squeezing, each run of two or more all-zero rows becomes a single
+    /// longer range.
+    fn data_rows(&self, i: usize) -> Vec<Range<usize>> {
+        let width = self.data_width;
+        let mut out: Vec<Range<usize>> = vec![];
+        let mut prev_zero = false;
+        for (o, c) in self.shown_data(i).chunks(width).enumerate() {
+            let zero = self.data_squeeze
+                && c.len() == width
+                && c.iter().all(|b| *b == 0);
+            match out.last_mut() {
+                Some(prev) if zero && prev_zero => prev.end += width,
+                _ => out.push(o * width..o * width + c.len()),
             }
-            self.data_width = data_width;
+            prev_zero = zero;
         }
+        out
+    }
+
+    /// Returns the offset within the shown bytes of a hex dump row
+    fn row_offset(&self, i: usize, row: usize) -> usize {
+        self.data_rows(i).get(row).map_or(0, |r| r.start)
+    }
+
+    /// Returns the hex dump row containing an offset within the shown bytes
+    fn offset_row(&self, i: usize, offset: usize) -> usize {
+        let rows = self.data_rows(i);
+        rows.iter()
+            .position(|r| offset < r.end)
+            .unwrap_or(rows.len().saturating_sub(1))
     }
 
     fn render_data(&mut self, frame: &mut Frame, area: Rect, focus: bool) {
@@ -616,105 +1892,229 @@ impl App {
         let selected_row_style = Style::new().add_modifier(Modifier::REVERSED);
 
         const OFFSET_COL: u16 = 8;
-        let available_width = area.width - 3;
-        let width = if available_width >= OFFSET_COL + 1 + 16 * 3 + 16 {
+        let bs = self.data_grouping.bytes();
+        let cols = self.data_grouping.columns(self.data_interp);
+        let available_width = usize::from(area.width.saturating_sub(3));
+        let width = if available_width
+            >= usize::from(OFFSET_COL) + 1 + 16 / bs * (cols + 1) + 16
+        {
             16
         } else {
             8
         };
         self.resize_data(width);
 
-        let bs = self.data_grouping.bytes();
         let header = std::iter::once(Cell::from("OFFSET"))
-            .chain(
-                (0..width / bs).map(|i| Cell::from(format!("{:02x}", i * bs))),
-            )
+            .chain((0..width / bs).map(|i| {
+                let s = Line::from(format!("{:02x}", i * bs));
+                Cell::from(if self.data_interp == DataInterp::Hex {
+                    s
+                } else {
+                    s.right_aligned()
+                })
+            }))
             .collect::<Row>()
             .style(header_style);
-        let Some(i) = self.item_state.selected() else {
+        let Some(i) = self.selected() else {
             return;
         };
-        let rows =
-            self.items[i].data.chunks(width).enumerate().map(|(o, c)| {
-                let offset = o * width;
-                std::iter::once(
-                    Line::from(format!("{:06x}", offset))
-                        .style(Style::new().add_modifier(Modifier::DIM))
-                        .into(),
-                )
-                .chain(c.chunks(bs).map(|c| {
-                    let mut s = String::new();
-                    match self.data_endian {
-                        Endian::Little => {
-                            for b in c.iter().rev() {
-                                s += &format!("{b:02x}");
+        if let Some(spec) = self.overlay_for(i) {
+            let spec = spec.clone();
+            self.render_overlay(&spec, i, frame, area, focus);
+            return;
+        }
+        // Rows holding the entry header are labeled in a different color
+        let header_len = match &self.items[i].entry {
+            Item::Entry(e) if self.data_full => std::mem::size_of_val(e),
+            _ => 0,
+        };
+        let data = self.shown_data(i);
+        let data_rows = self.data_rows(i);
+        let rows = data_rows.iter().map(|r| {
+            let offset = r.start;
+            let c = &data[r.clone()];
+            if r.len() > width {
+                // The message is drawn after the table, since it's wider
+                // than any one cell
+                return std::iter::once(Cell::from(
+                    Line::from(format!("{offset:06x}"))
+                        .style(Style::new().add_modifier(Modifier::DIM)),
+                ))
+                .collect::<Row>();
+            }
+            std::iter::once(
+                Line::from(format!("{:06x}", offset))
+                    .style(if offset < header_len {
+                        Style::new().fg(Color::Yellow)
+                    } else {
+                        Style::new().add_modifier(Modifier::DIM)
+                    })
+                    .into(),
+            )
+            .chain(c.chunks(bs).map(|c| {
+                let line =
+                    if self.data_interp == DataInterp::Hex || c.len() < bs {
+                        let mut s = String::new();
+                        match self.data_endian {
+                            Endian::Little => {
+                                for b in c.iter().rev() {
+                                    s += &format!("{b:02x}");
+                                }
                             }
-                        }
-                        Endian::Big => {
-                            for b in c.iter() {
-                                s += &format!("{b:02x}");
+                            Endian::Big => {
+                                for b in c.iter() {
+                                    s += &format!("{b:02x}");
+                                }
                             }
                         }
-                    }
-                    Cell::from(Line::from(s).style(if self.data_colors {
-                        Self::data_style(c)
+                        Line::from(s)
                     } else {
-                        Style::new()
-                    }))
+                        Line::from(self.interpret(c)).right_aligned()
+                    };
+                Cell::from(line.style(if self.data_colors {
+                    Self::data_style(c)
+                } else {
+                    Style::new()
                 }))
-                .chain(
-                    // Empty cells to fill out the remaining size
-                    std::iter::repeat_n(
-                        Cell::from(""),
-                        width / bs - c.len() / bs,
-                    ),
-                )
-                .chain(std::iter::once(
-                    c.iter()
-                        .map(|b| {
-                            if b.is_ascii() && !b.is_ascii_control() {
-                                *b as char
-                            } else {
-                                '.'
-                            }
-                        })
-                        .collect::<String>()
-                        .into(),
-                ))
-                .collect::<Row>()
-            });
-        let title = match self.items[i].entry {
-            Item::Header(..) => "Raw header",
-            Item::Padding => "Padding data",
-            Item::Entry(..) => "Entry data",
+            }))
+            .chain(
+                // Empty cells to fill out the remaining size
+                std::iter::repeat_n(Cell::from(""), width / bs - c.len() / bs),
+            )
+            .chain(std::iter::once(self.data_text.decode(c).into()))
+            .collect::<Row>()
+        });
+        let title = match &self.items[i].entry {
+            Item::Header(..) => "Raw header".to_owned(),
+            Item::Padding => "Padding data".to_owned(),
+            Item::Trailing => "Trailing data".to_owned(),
+            Item::Truncated(e) => format!("Truncated entry: {e}"),
+            Item::Entry(..) if self.data_full => {
+                "Entry header and data".to_owned()
+            }
+            Item::Entry(..) => "Entry data".to_owned(),
         };
+        let t =
+            Table::new(
+                rows,
+                std::iter::once(Constraint::Length(OFFSET_COL))
+                    .chain((0..width / bs).map(|_| {
+                        Constraint::Length(u16::try_from(cols).unwrap())
+                    }))
+                    .chain(std::iter::once(Constraint::Length(
+                        u16::try_from(width).unwrap(),
+                    ))),
+            )
+            .header(header)
+            .row_highlight_style(selected_row_style);
+        let mut block = Block::new()
+            .borders(Borders::ALL)
+            .border_style(Self::border_style(focus))
+            .title(title)
+            .title_style(Style::reset().add_modifier(Modifier::BOLD));
+        if let Some(hmac) = self.hmac_line(&self.items[i]) {
+            block = block.title_bottom(hmac);
+        }
+        let t = t.block(block);
+
+        frame.render_stateful_widget(t, area, &mut self.data_state);
+
+        // Draw squeezed rows across the byte columns, below the header row
+        let inner = area.inner(Margin {
+            vertical: 1,
+            horizontal: 1,
+        });
+        let x = inner.x + OFFSET_COL + 1;
+        let max_width = usize::from(inner.right().saturating_sub(x));
+        let first = self.data_state.offset();
+        for (y, r) in
+            (inner.y + 1..inner.bottom()).zip(data_rows.iter().skip(first))
+        {
+            if r.len() > width {
+                frame.buffer_mut().set_stringn(
+                    x,
+                    y,
+                    format!("* ({:#x} bytes of zeros)", r.len()),
+                    max_width,
+                    Style::new().add_modifier(Modifier::DIM),
+                );
+            }
+        }
+
+        // Draw the scroll bar
+        if let Some(j) = self.data_state.selected() {
+            let mut data_scroll_state =
+                ScrollbarState::new(data_rows.len()).position(j);
+            frame.render_stateful_widget(
+                Scrollbar::default()
+                    .orientation(ScrollbarOrientation::VerticalRight)
+                    .begin_symbol(None)
+                    .end_symbol(None)
+                    .style(Self::scrollbar_style(focus)),
+                area.inner(Margin {
+                    vertical: 1,
+                    horizontal: 1,
+                }),
+                &mut data_scroll_state,
+            );
+        }
+    }
+
+    /// Renders an entry's data as the fields of a struct overlay
+    fn render_overlay(
+        &mut self,
+        spec: &Spec,
+        i: usize,
+        frame: &mut Frame,
+        area: Rect,
+        focus: bool,
+    ) {
+        let header_style = Style::new().add_modifier(Modifier::BOLD);
+        let selected_row_style = Style::new().add_modifier(Modifier::REVERSED);
+
+        let header = Row::new(["OFFSET", "TYPE", "VALUE"]).style(header_style);
+        let fields = spec.layout(
+            self.shown_data(i),
+            matches!(self.data_endian, Endian::Big),
+        );
+        let n = fields.len();
+        let rows = fields.into_iter().map(|f| {
+            Row::new([
+                Cell::from(
+                    Line::from(format!("{:06x}", f.offset))
+                        .style(Style::new().add_modifier(Modifier::DIM)),
+                ),
+                Cell::from(f.ty),
+                Cell::from(f.value),
+            ])
+        });
+        let mut block = Block::new()
+            .borders(Borders::ALL)
+            .border_style(Self::border_style(focus))
+            .title(if self.data_full {
+                format!("Entry header and data as {}", spec.text())
+            } else {
+                format!("Entry data as {}", spec.text())
+            })
+            .title_style(Style::reset().add_modifier(Modifier::BOLD));
+        if let Some(hmac) = self.hmac_line(&self.items[i]) {
+            block = block.title_bottom(hmac);
+        }
         let t = Table::new(
             rows,
-            std::iter::once(Constraint::Length(OFFSET_COL))
-                .chain((0..width / bs).map(|_| {
-                    Constraint::Length(u16::try_from(bs * 2).unwrap())
-                }))
-                .chain(std::iter::once(Constraint::Length(
-                    u16::try_from(width).unwrap(),
-                ))),
+            [
+                Constraint::Length(8),
+                Constraint::Length(12),
+                Constraint::Fill(1),
+            ],
         )
         .header(header)
         .row_highlight_style(selected_row_style)
-        .block(
-            Block::new()
-                .borders(Borders::ALL)
-                .border_style(Self::border_style(focus))
-                .title(title)
-                .title_style(Style::reset().add_modifier(Modifier::BOLD)),
-        );
-
+        .block(block);
         frame.render_stateful_widget(t, area, &mut self.data_state);
 
-        // Draw the scroll bar
         if let Some(j) = self.data_state.selected() {
-            let mut data_scroll_state =
-                ScrollbarState::new(self.items[i].data.len().div_ceil(width))
-                    .position(j);
+            let mut data_scroll_state = ScrollbarState::new(n).position(j);
             frame.render_stateful_widget(
                 Scrollbar::default()
                     .orientation(ScrollbarOrientation::VerticalRight)
@@ -730,6 +2130,34 @@ impl App {
         }
     }
 
+    /// Formats a full group of bytes as a signed integer or float
+    fn interpret(&self, c: &[u8]) -> String {
+        let mut buf = [0u8; 8];
+        let buf = &mut buf[..c.len()];
+        buf.copy_from_slice(c);
+        if matches!(self.data_endian, Endian::Big) {
+            buf.reverse();
+        }
+        // `buf` is now little-endian; sign-extend it to 64 bits
+        let fill = if buf.last().is_some_and(|b| b & 0x80 != 0) {
+            0xff
+        } else {
+            0
+        };
+        let mut v = [fill; 8];
+        v[..buf.len()].copy_from_slice(buf);
+        let v = i64::from_le_bytes(v);
+        match (self.data_interp, c.len()) {
+            (DataInterp::Float, 4) => {
+                format!("{:.3e}", f32::from_bits(v as u32))
+            }
+            (DataInterp::Float, 8) => {
+                format!("{:.3e}", f64::from_bits(v as u64))
+            }
+            _ => v.to_string(),
+        }
+    }
+
     fn data_style(b: &[u8]) -> Style {
         let style = Style::new();
         if b.iter().all(|b| *b == 0) {
@@ -801,33 +2229,37 @@ impl App {
             .style(header_style);
         let cf = |t| Cell::from(Span::from(t));
         let cfr = |t| Cell::from(Line::from(t).alignment(Alignment::Right));
-        let rows = self.items.iter().map(|item| match &item.entry {
+        // Bookmarks are marked in the otherwise-unused first character
+        let offset = |i: usize, item: &Entry| {
+            let mark = if self.bookmarks.contains(&i) {
+                "●"
+            } else {
+                " "
+            };
+            Cell::from(Line::from(vec![
+                Span::styled(mark, Style::new().fg(Color::Cyan)),
+                Span::raw(format!("{:05x}", item.offset)),
+            ]))
+        };
+        let rows = self.rows.iter().map(|&i| (i, &self.items[i]));
+        let rows = rows.map(|(i, item)| match &item.entry {
             Item::Entry(entry) => {
-                let group = entry.group().unwrap();
+                let group = entry.group();
                 let cancelled = entry.cancelled();
-                let group_style = if cancelled {
-                    Style::new().add_modifier(Modifier::DIM)
-                } else {
-                    let color = match group {
-                        apob::ApobGroup::MEMORY => Color::Blue,
-                        apob::ApobGroup::DF => Color::LightBlue,
-                        apob::ApobGroup::CCX => Color::Red,
-                        apob::ApobGroup::NBIO => Color::LightGreen,
-                        apob::ApobGroup::FCH => Color::LightRed,
-                        apob::ApobGroup::PSP => Color::LightCyan,
-                        apob::ApobGroup::GENERAL => Color::Magenta,
-                        apob::ApobGroup::SMBIOS => Color::Green,
-                        apob::ApobGroup::FABRIC => Color::Cyan,
-                        apob::ApobGroup::APCB => Color::LightMagenta,
-                    };
-                    Style::new().fg(color)
+                let group_style = match group {
+                    _ if cancelled => Style::new().add_modifier(Modifier::DIM),
+                    Some(g) => Style::new().fg(crate::color::group(g)),
+                    None => Style::new(),
                 };
-                let specialized = Self::specialized(item.entry).is_some();
+                let specialized = self.specialized(i).is_some();
                 [
-                    cfr(format!("{:05x}", item.offset)),
+                    offset(i, item),
                     cf(format!(
-                        "{:?}{}",
-                        group,
+                        "{}{}",
+                        group.map_or_else(
+                            || format!("{:#x}", entry.raw_group()),
+                            |g| apob::group_name(g).to_owned()
+                        ),
                         if cancelled {
                             "*"
                         } else if specialized {
@@ -837,8 +2269,8 @@ impl App {
                         }
                     ))
                     .style(group_style),
-                    cfr(format!("{:#04x}", entry.ty & !apob::APOB_CANCELLED)),
-                    cfr(format!("{:x}", entry.inst)),
+                    cfr(format!("{:#04x}", entry.type_id())),
+                    cfr(format!("{:x}", { entry.inst })),
                     cfr(format!(
                         "{:x}",
                         entry.size as usize - std::mem::size_of_val(entry)
@@ -848,7 +2280,7 @@ impl App {
                 .collect::<Row>()
             }
             Item::Header(_) => [
-                cfr(format!("{:05x}", item.offset)),
+                offset(i, item),
                 cf("HEADER+".to_owned()).style(Style::new().fg(Color::Yellow)),
                 cfr("--".to_owned()),
                 cfr("--".to_owned()),
@@ -857,12 +2289,41 @@ impl App {
             .into_iter()
             .collect::<Row>(),
             Item::Padding => [
-                cfr(format!("{:05x}", item.offset)),
-                cf("PADDING".to_owned())
-                    .style(Style::new().fg(Color::LightRed)),
+                offset(i, item),
+                cf("PADDING".to_owned()).style(
+                    if item.data().iter().all(|b| *b == 0) {
+                        Style::new().fg(Color::LightRed)
+                    } else {
+                        // Non-zero padding suggests a corrupt blob
+                        Style::new().fg(Color::Red).add_modifier(Modifier::BOLD)
+                    },
+                ),
+                cfr("--".to_owned()),
+                cfr("--".to_owned()),
+                cfr(format!("{:x}", item.data().len())),
+            ]
+            .into_iter()
+            .collect::<Row>(),
+            // Data past the declared size is usually junk appended by tools
+            Item::Trailing => [
+                offset(i, item),
+                cf("TRAILING".to_owned()).style(
+                    Style::new().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ),
+                cfr("--".to_owned()),
+                cfr("--".to_owned()),
+                cfr(format!("{:x}", item.data().len())),
+            ]
+            .into_iter()
+            .collect::<Row>(),
+            Item::Truncated(..) => [
+                offset(i, item),
+                cf("TRUNC".to_owned()).style(
+                    Style::new().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ),
                 cfr("--".to_owned()),
                 cfr("--".to_owned()),
-                cfr(format!("{:x}", item.data.len())),
+                cfr(format!("{:x}", item.data().len())),
             ]
             .into_iter()
             .collect::<Row>(),
@@ -893,7 +2354,7 @@ impl App {
         // Draw the scroll bar
         if let Some(i) = self.item_state.selected() {
             let mut item_scroll_state =
-                ScrollbarState::new(self.items.len()).position(i);
+                ScrollbarState::new(self.rows.len()).position(i);
             frame.render_stateful_widget(
                 Scrollbar::default()
                     .orientation(ScrollbarOrientation::VerticalRight)
@@ -911,7 +2372,7 @@ impl App {
 
     pub fn next_item_row(&mut self, d: usize) {
         let i = match self.item_state.selected() {
-            Some(i) => (i + d).min(self.items.len() - 1),
+            Some(i) => (i + d).min(self.rows.len().saturating_sub(1)),
             None => 0,
         };
         self.set_item_scroll(i);
@@ -925,17 +2386,441 @@ impl App {
         self.set_item_scroll(i);
     }
 
-    fn set_item_scroll(&mut self, i: usize) {
-        self.item_state.select(Some(i));
+    fn goto_prompt_key(&mut self, code: KeyCode) {
+        let Some(p) = self.goto_prompt.as_mut() else {
+            return;
+        };
+        match code {
+            KeyCode::Char(c) if c.is_ascii_hexdigit() => p.push(c),
+            KeyCode::Backspace => {
+                p.pop();
+            }
+            KeyCode::Enter => {
+                let p = self.goto_prompt.take().unwrap();
+                match usize::from_str_radix(&p, 16) {
+                    Ok(offset) => self.goto_offset(offset),
+                    Err(_) => self.status = Some("invalid offset".to_owned()),
+                }
+            }
+            KeyCode::Esc => self.goto_prompt = None,
+            _ => (),
+        }
+    }
+
+    fn open_decoder_picker(&mut self) {
+        let Some(i) = self.selected() else {
+            return;
+        };
+        if !matches!(self.items[i].entry, Item::Entry(..)) {
+            self.status = Some("only entries can be decoded".to_owned());
+            return;
+        }
+        // Row 0 means the entry's own type
+        let row = self.decode_as.get(&i).map_or(0, |d| d + 1);
+        self.decoder_picker = Some(row);
+    }
+
+    fn decoder_picker_key(&mut self, code: KeyCode) {
+        let Some(row) = self.decoder_picker.as_mut() else {
+            return;
+        };
+        let rows = self.decoders.names().count() + 1;
+        match code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                *row = (*row + 1).min(rows - 1);
+            }
+            KeyCode::Char('k') | KeyCode::Up => *row = row.saturating_sub(1),
+            KeyCode::Enter => {
+                let row = self.decoder_picker.take().unwrap();
+                let Some(i) = self.selected() else {
+                    return;
+                };
+                if row == 0 {
+                    self.decode_as.remove(&i);
+                } else {
+                    self.decode_as.insert(i, row - 1);
+                }
+                self.clear_specialized();
+            }
+            KeyCode::Esc => self.decoder_picker = None,
+            _ => (),
+        }
+    }
+
+    fn overlay_prompt_key(&mut self, code: KeyCode) {
+        let Some(p) = self.overlay_prompt.as_mut() else {
+            return;
+        };
+        match code {
+            KeyCode::Char(c) => p.push(c),
+            KeyCode::Backspace => {
+                p.pop();
+            }
+            KeyCode::Enter => {
+                let p = self.overlay_prompt.take().unwrap();
+                // An empty spec goes back to the hex dump
+                if p.trim().is_empty() {
+                    self.overlay = None;
+                } else {
+                    match Spec::parse(&p) {
+                        Ok(spec) => self.overlay = Some(spec),
+                        Err(e) => self.status = Some(e),
+                    }
+                }
+                if let Some(row) = self.item_state.selected() {
+                    self.set_item_scroll(row);
+                }
+            }
+            KeyCode::Esc => self.overlay_prompt = None,
+            _ => (),
+        }
+    }
+
+    /// Selects the item containing the given file offset
+    ///
+    /// The data pane is scrolled to the row containing the offset; if the
+    /// offset lands in an entry's header, the first row is selected.
+    fn goto_offset(&mut self, offset: usize) {
+        let found = self.rows.iter().enumerate().find(|(_, &i)| {
+            let item = &self.items[i];
+            (item.offset..item.offset + item.size()).contains(&offset)
+        });
+        let Some((row, &i)) = found else {
+            self.status = Some(format!("offset {offset:#x} is not visible"));
+            return;
+        };
+        let item = &self.items[i];
+        let data_start = if self.data_full {
+            item.offset
+        } else {
+            item.offset + item.size() - item.data().len()
+        };
+        let data_row = self.offset_row(i, offset.saturating_sub(data_start));
+        self.set_item_scroll(row);
+        self.set_data_scroll(data_row);
+    }
+
+    fn copy_raw(&mut self) {
+        let Some(i) = self.selected() else {
+            return;
+        };
+        let text = self.items[i]
+            .data()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>();
+        self.status = Some(match copy_to_clipboard(text) {
+            Ok(()) => "copied data to clipboard".to_owned(),
+            Err(e) => format!("copy failed: {e}"),
+        });
+    }
+
+    fn copy_decoded(&mut self) {
+        let Some(i) = self.selected() else {
+            return;
+        };
+        let text = match &self.items[i].entry {
+            Item::Header(h) => format!("{h:?}"),
+            Item::Entry(..) if self.decode(i).is_some() => {
+                let mut out = vec![];
+                let section = self.decode(i).unwrap();
+                crate::write_section(&mut out, section, false, None, false)
+                    .unwrap();
+                String::from_utf8_lossy(&out).into_owned()
+            }
+            _ => {
+                self.status = Some("no decoded form for this entry".to_owned());
+                return;
+            }
+        };
+        self.status = Some(match copy_to_clipboard(text) {
+            Ok(()) => "copied decoded text to clipboard".to_owned(),
+            Err(e) => format!("copy failed: {e}"),
+        });
+    }
+
+    /// Writes the selected entry to a timestamped file in the working
+    /// directory, as text if it has a specialized view and as hex otherwise
+    fn write_view(&mut self) {
+        let Some(i) = self.selected() else {
+            return;
+        };
+        let item = &self.items[i];
+        let mut out = vec![];
+        // The strings view applies to every entry, including those which we
+        // can't decode, so those fall back to a hex dump
+        let section = self.specialized(i).and_then(|_| self.decode(i));
+        match (&item.entry, section) {
+            (Item::Header(h), _) => writeln!(out, "{h:?}"),
+            (Item::Entry(..), Some(section)) => {
+                crate::write_section(&mut out, section, false, None, false)
+            }
+            _ => crate::print_hex(&mut out, item.data(), 0, 16),
+        }
+        .unwrap();
+        let secs = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let path = format!("apob-{:#07x}-{secs}.txt", item.offset);
+        self.status = Some(match std::fs::write(&path, out) {
+            Ok(()) => format!("wrote {path}"),
+            Err(e) => format!("write failed: {e}"),
+        });
+    }
+
+    /// Selects the next (or previous) visible entry in the selected entry's
+    /// group, wrapping around
+    fn jump_in_group(&mut self, forward: bool) {
+        let group_of = |i: usize| match &self.items[i].entry {
+            Item::Entry(e) => e.group(),
+            Item::Header(..)
+            | Item::Padding
+            | Item::Trailing
+            | Item::Truncated(..) => None,
+        };
+        let (Some(row), Some(group)) = (
+            self.item_state.selected(),
+            self.selected().and_then(group_of),
+        ) else {
+            return;
+        };
+        let n = self.rows.len();
+        let found = (1..n)
+            .map(|d| (if forward { row + d } else { row + n - d }) % n)
+            .find(|&r| group_of(self.rows[r]) == Some(group));
+        if let Some(r) = found {
+            self.set_item_scroll(r);
+        }
+    }
+
+    fn toggle_bookmark(&mut self) {
+        if let Some(i) = self.selected() {
+            if !self.bookmarks.remove(&i) {
+                self.bookmarks.insert(i);
+            }
+        }
+    }
+
+    /// Selects the next or previous visible bookmarked item, wrapping around
+    fn jump_to_bookmark(&mut self, forward: bool) {
+        let row = self.item_state.selected().unwrap_or(0);
+        let n = self.rows.len();
+        let found = (1..=n)
+            .map(|d| (if forward { row + d } else { row + n - d }) % n)
+            .find(|&r| self.bookmarks.contains(&self.rows[r]));
+        match found {
+            Some(r) => self.set_item_scroll(r),
+            None => self.status = Some("no bookmarks".to_owned()),
+        }
+    }
+
+    /// Returns the index into `items` of the selected row
+    fn selected(&self) -> Option<usize> {
+        self.item_state
+            .selected()
+            .and_then(|r| self.rows.get(r).copied())
+    }
+
+    /// Sets the order of rows in the entry table
+    pub fn set_sort(&mut self, sort: SortOrder) {
+        self.sort = sort;
+        self.update_rows();
+    }
+
+    /// Sets the key used to check the HMAC of the selected entry
+    #[cfg(feature = "hmac")]
+    pub fn set_hmac_key(&mut self, key: Vec<u8>) {
+        self.hmac_key = Some(key);
+    }
+
+    /// Describes the selected entry's stored HMAC, checking it if we have a
+    /// key
+    fn hmac_line(&self, item: &Entry) -> Option<String> {
+        let Item::Entry(e) = &item.entry else {
+            return None;
+        };
+        let hex = e
+            .hmac
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>();
+        #[cfg(feature = "hmac")]
+        if let Some(key) = &self.hmac_key {
+            let mark = if e.verify_hmac(item.data(), key) {
+                "✓"
+            } else {
+                "✗"
+            };
+            return Some(format!(" {mark} HMAC {hex} "));
+        }
+        Some(format!(" HMAC {hex} "))
+    }
+
+    /// Adds items from `items` as the viewer runs, rather than up front
+    ///
+    /// `view` is restored once loading finishes, if it still matches.
+    pub fn load_lazily(
+        &mut self,
+        items: Box<dyn Iterator<Item = anyhow::Result<Entry>>>,
+        view: Option<ViewState>,
+    ) {
+        self.loading = Some(Loading { items, view });
+    }
+
+    /// Adds items for a single frame's worth of time
+    fn load_more(&mut self) {
+        let Some(loading) = &mut self.loading else {
+            return;
+        };
+        let deadline = Instant::now() + LOAD_TIME;
+        let mut done = true;
+        for item in loading.items.by_ref() {
+            match item {
+                Ok(item) => self.items.push(item),
+                Err(e) => {
+                    self.status = Some(format!("load failed: {e:#}"));
+                    break;
+                }
+            }
+            if Instant::now() >= deadline {
+                done = false;
+                break;
+            }
+        }
+        let view = if done {
+            self.loading.take().and_then(|l| l.view)
+        } else {
+            None
+        };
+        self.update_rows();
+        if let Some(v) = view.filter(|v| v.item_count == self.items.len()) {
+            self.restore(v);
+        }
+    }
+
+    pub fn follow(&mut self, follow: Follow) {
+        self.follow = Some(follow);
+    }
+
+    /// Reloads the file if it has changed, as requested by [`App::follow`]
+    fn check_follow(&mut self) {
+        let Some(follow) = &mut self.follow else {
+            return;
+        };
+        if !follow.changed() {
+            return;
+        }
+        // The file may be caught halfway through being rewritten, in which
+        // case we keep the old contents until it changes again
+        match (follow.reload)() {
+            Ok(items) => {
+                self.replace_items(items);
+                self.status = Some("reloaded".to_owned());
+            }
+            Err(e) => self.status = Some(format!("reload failed: {e:#}")),
+        }
+    }
+
+    /// Swaps in freshly loaded items
+    ///
+    /// The selection follows the selected entry's group, type, and instance;
+    /// if no entry matches, the selected row is clamped instead.
+    fn replace_items(&mut self, items: Vec<Entry>) {
+        let key = |item: &Entry| match &item.entry {
+            Item::Entry(e) => Some((e.raw_group(), e.type_id(), e.inst)),
+            _ => None,
+        };
+        let prev_row = self.item_state.selected().unwrap_or(0);
+        let prev = self.selected().and_then(|i| key(&self.items[i]));
+        // Per-item state is keyed by index, which is meaningless if entries
+        // were added or removed
+        if items.len() != self.items.len() {
+            self.data_scroll_cache.clear();
+            self.bookmarks.clear();
+            self.decode_as.clear();
+        }
+        self.file.size = items.first().map_or(0, |item| item.file.len());
+        // Anything still loading is from the old contents
+        self.loading = None;
+        self.items = items;
+        self.clear_specialized();
+        self.update_rows();
+        let row = prev
+            .and_then(|k| {
+                self.rows
+                    .iter()
+                    .position(|&i| key(&self.items[i]) == Some(k))
+            })
+            .unwrap_or(prev_row.min(self.rows.len().saturating_sub(1)));
+        self.set_item_scroll(row);
+    }
+
+    /// Recomputes visible rows, keeping the selected item where possible
+    fn update_rows(&mut self) {
+        let prev = self.selected().unwrap_or(0);
+        self.rows = (0..self.items.len())
+            .filter(|&i| match &self.items[i].entry {
+                Item::Entry(e) => !(self.hide_cancelled && e.cancelled()),
+                Item::Header(..) | Item::Padding => !self.hide_synthetic,
+                Item::Trailing | Item::Truncated(..) => true,
+            })
+            .collect();
+        self.rows
+            .sort_by_key(|&i| self.items[i].sort_key(self.sort));
+        self.set_item_scroll(self.row_of(prev));
+    }
+
+    /// Returns the row showing `item`, or the next visible item after it in
+    /// the file
+    fn row_of(&self, item: usize) -> usize {
+        self.rows
+            .iter()
+            .enumerate()
+            .filter(|(_, &i)| i >= item)
+            .min_by_key(|(_, &i)| i)
+            .map(|(r, _)| r)
+            .unwrap_or(self.rows.len().saturating_sub(1))
+    }
+
+    fn set_item_scroll(&mut self, row: usize) {
+        let Some(&i) = self.rows.get(row) else {
+            self.item_state.select(None);
+            return;
+        };
+        self.item_state.select(Some(row));
+        self.data_scroll_max = match self.overlay_for(i) {
+            Some(spec) => spec.layout(self.shown_data(i), false).len().max(1),
+            None => self.data_rows(i).len(),
+        };
+        // The cached row may be past the end if the header has been hidden
+        let scroll = self.data_scroll_cache.get(&i).cloned().unwrap_or(0);
         self.data_state
-            .select(Some(self.data_scroll_cache.get(&i).cloned().unwrap_or(0)));
-        self.data_scroll_max =
-            self.items[i].data.len().div_ceil(self.data_width);
+            .select(Some(scroll.min(self.data_scroll_max.saturating_sub(1))));
+    }
+
+    /// Returns the struct overlay to show for an item, if any
+    ///
+    /// Only entries are overlaid, since the other items have no type.
+    fn overlay_for(&self, i: usize) -> Option<&Spec> {
+        match self.items[i].entry {
+            Item::Entry(..) => self.overlay.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the bytes shown in the data pane for an item
+    fn shown_data(&self, i: usize) -> &[u8] {
+        let item = &self.items[i];
+        if self.data_full {
+            item.bytes()
+        } else {
+            item.data()
+        }
     }
 
     pub fn next_data_row(&mut self, d: usize) {
         let i = match self.data_state.selected() {
-            Some(i) => (i + d).min(self.data_scroll_max - 1),
+            Some(i) => (i + d).min(self.data_scroll_max.saturating_sub(1)),
             None => 0,
         };
         self.set_data_scroll(i);
@@ -950,9 +2835,21 @@ impl App {
     }
 
     pub fn set_data_scroll(&mut self, i: usize) {
-        if let Some(j) = self.item_state.selected() {
+        if let Some(j) = self.selected() {
             self.data_scroll_cache.insert(j, i);
         }
         self.data_state.select(Some(i));
     }
 }
+
+#[cfg(feature = "clipboard")]
+fn copy_to_clipboard(text: String) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut c| c.set_text(text))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn copy_to_clipboard(_text: String) -> Result<(), String> {
+    Err("built without clipboard support".to_owned())
+}
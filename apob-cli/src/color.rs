@@ -0,0 +1,164 @@
+//! Colors shared by the interactive viewer and plain output
+
+use ratatui::{
+    crossterm::style::{style, Stylize},
+    style::Color,
+};
+use std::{io::IsTerminal, path::Path, sync::OnceLock};
+
+/// When to color plain output
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Only when stdout is a terminal and `NO_COLOR` is unset
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Decides whether output written to stdout should be colored
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                // Per no-color.org, an empty `NO_COLOR` doesn't count
+                let no_color =
+                    std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+                !no_color && std::io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+/// Colors which override the built-in ones, loaded from a theme file
+///
+/// A theme is a TOML file with a `groups` table mapping group names to colors
+/// and an `events` table mapping event class names to colors:
+///
+/// ```toml
+/// [groups]
+/// MEMORY = "light-blue"
+///
+/// [events]
+/// WARN = "#ffaf00"
+/// ```
+///
+/// Colors may be named (as accepted by [`Color`]'s `FromStr`), `#rrggbb`, or
+/// an index into the 256-color palette.  Groups and classes which aren't
+/// listed keep their built-in colors.
+#[derive(Default)]
+pub struct Theme {
+    groups: Vec<(apob::ApobGroup, Color)>,
+    events: Vec<(apob::MilanApobEventClass, Color)>,
+}
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+impl Theme {
+    /// Loads a theme file, returning it along with a warning for each entry
+    /// which was ignored
+    pub fn load(path: &Path) -> anyhow::Result<(Self, Vec<String>)> {
+        use anyhow::Context;
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {path:?}"))?;
+        let table = text
+            .parse::<toml::Table>()
+            .with_context(|| format!("failed to parse {path:?}"))?;
+        let mut theme = Theme::default();
+        let mut warnings = vec![];
+        for (key, value) in &table {
+            if key != "groups" && key != "events" {
+                warnings.push(format!("unknown table `{key}`"));
+                continue;
+            }
+            let Some(entries) = value.as_table() else {
+                warnings.push(format!("`{key}` is not a table"));
+                continue;
+            };
+            for (name, value) in entries {
+                let color = match value {
+                    toml::Value::String(s) => s.parse().ok(),
+                    toml::Value::Integer(i) => {
+                        u8::try_from(*i).ok().map(Color::Indexed)
+                    }
+                    _ => None,
+                };
+                let Some(color) = color else {
+                    warnings.push(format!("invalid color {value} for {name}"));
+                    continue;
+                };
+                if key == "groups" {
+                    match apob::ApobGroup::from_name(name) {
+                        Some(g) => theme.groups.push((g, color)),
+                        None => warnings.push(format!("unknown group {name}")),
+                    }
+                } else {
+                    match apob::MilanApobEventClass::from_name(name) {
+                        Some(c) => theme.events.push((c, color)),
+                        None => {
+                            warnings.push(format!("unknown event class {name}"))
+                        }
+                    }
+                }
+            }
+        }
+        Ok((theme, warnings))
+    }
+
+    /// Makes this the theme used by [`group`] and [`event_class`]
+    ///
+    /// Only the first call has any effect.
+    pub fn install(self) {
+        let _ = THEME.set(self);
+    }
+}
+
+pub fn group(group: apob::ApobGroup) -> Color {
+    let themed = THEME
+        .get()
+        .and_then(|t| t.groups.iter().find(|(g, _)| *g == group));
+    if let Some((_, c)) = themed {
+        return *c;
+    }
+    match group {
+        apob::ApobGroup::MEMORY => Color::Blue,
+        apob::ApobGroup::DF => Color::LightBlue,
+        apob::ApobGroup::CCX => Color::Red,
+        apob::ApobGroup::NBIO => Color::LightGreen,
+        apob::ApobGroup::FCH => Color::LightRed,
+        apob::ApobGroup::PSP => Color::LightCyan,
+        apob::ApobGroup::GENERAL => Color::Magenta,
+        apob::ApobGroup::SMBIOS => Color::Green,
+        apob::ApobGroup::FABRIC => Color::Cyan,
+        apob::ApobGroup::APCB => Color::LightMagenta,
+    }
+}
+
+pub fn event_class(class: apob::MilanApobEventClass) -> Color {
+    let themed = THEME
+        .get()
+        .and_then(|t| t.events.iter().find(|(c, _)| *c == class));
+    if let Some((_, c)) = themed {
+        return *c;
+    }
+    match class {
+        apob::MilanApobEventClass::ALERT => Color::Green,
+        apob::MilanApobEventClass::WARN => Color::Blue,
+        apob::MilanApobEventClass::ERROR => Color::Magenta,
+        apob::MilanApobEventClass::CRIT => Color::Yellow,
+        apob::MilanApobEventClass::FATAL => Color::Red,
+    }
+}
+
+/// Wraps `s` in escape codes for `color`, if `enabled`
+///
+/// Padding should be applied before painting, since the escape codes would
+/// otherwise count towards the width.
+pub fn paint(s: &str, color: Color, enabled: bool) -> String {
+    if enabled {
+        style(s).with(color.into()).to_string()
+    } else {
+        s.to_owned()
+    }
+}
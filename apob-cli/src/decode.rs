@@ -0,0 +1,244 @@
+//! Registry of decoders for known entry types
+//!
+//! Each decoder turns an entry's payload into a [`DecodedSection`], which the
+//! text, JSON, and interactive renderers know how to display.  New entry types
+//! are added by writing a decoder and listing it in [`Registry::new`].
+
+use apob::{ApobError, ApobGroup};
+use zerocopy::FromBytes;
+
+/// Shortest run of printable characters reported by [`extract_strings`]
+pub const MIN_STRING_LEN: usize = 4;
+
+/// Finds runs of at least `min_len` printable ASCII characters, like
+/// `strings`, returning each with its offset in `data`
+pub fn extract_strings(data: &[u8], min_len: usize) -> Vec<(usize, String)> {
+    let mut out = vec![];
+    let mut offset = 0;
+    for run in
+        data.split(|&b| !(b.is_ascii_graphic() || b == b' ' || b == b'\t'))
+    {
+        if run.len() >= min_len {
+            out.push((offset, run.iter().map(|&b| char::from(b)).collect()));
+        }
+        offset += run.len() + 1;
+    }
+    out
+}
+
+/// Decoded contents of a single entry
+pub enum DecodedSection<'a> {
+    EventLog(&'a [apob::ApobEvent]),
+    MemMap {
+        high_phys: u64,
+        /// See [`apob::ApobSysMemMap::usable_bytes`]
+        usable: u64,
+        holes: &'a [apob::ApobSysMemMapHole],
+    },
+    PhyOverride(&'a apob::MilanApobPhyOverride),
+    PmuTrainFail(&'a [apob::PmuTfiEntry]),
+    Coremap(&'a apob::MilanApobCoremap),
+    Smbios(Vec<apob::SmbiosStructure<'a>>),
+    Apcb {
+        /// Header of the embedded APCB, if the payload starts with one
+        header: Option<&'a apob::ApcbHeader>,
+        /// Size of the payload
+        len: usize,
+    },
+    /// Recognized type whose payload is malformed
+    Invalid(ApobError),
+}
+
+pub trait ApobDecoder {
+    /// Describes the structure this decoder reads, for picking a decoder by
+    /// hand
+    fn name(&self) -> &'static str;
+    /// Checks whether this decoder handles the given group and type
+    fn matches(&self, group: ApobGroup, ty: u32) -> bool;
+    fn decode<'a>(&self, data: &'a [u8]) -> DecodedSection<'a>;
+}
+
+/// Ordered list of decoders, of which the first match is used
+pub struct Registry(Vec<Box<dyn ApobDecoder>>);
+
+impl Registry {
+    /// Builds the registry of decoders
+    pub fn new() -> Self {
+        Self(vec![
+            Box::new(EventLog),
+            Box::new(MemMap),
+            Box::new(PhyOverride),
+            Box::new(PmuTrainFail),
+            Box::new(Coremap),
+            Box::new(Smbios),
+            Box::new(Apcb),
+        ])
+    }
+
+    /// Returns the name of every decoder, in the order used by
+    /// [`Registry::decode_as`]
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.0.iter().map(|d| d.name())
+    }
+
+    /// Decodes a payload with the decoder at `index`, ignoring its type
+    ///
+    /// This is for payloads which we suspect have been mislabeled.
+    pub fn decode_as<'a>(
+        &self,
+        index: usize,
+        data: &'a [u8],
+    ) -> Option<DecodedSection<'a>> {
+        self.0.get(index).map(|d| d.decode(data))
+    }
+
+    /// Decodes an entry's payload, returning `None` if no decoder matches
+    pub fn decode<'a>(
+        &self,
+        entry: &apob::ApobEntry,
+        data: &'a [u8],
+    ) -> Option<DecodedSection<'a>> {
+        let group = entry.group()?;
+        let ty = entry.type_id();
+        self.0
+            .iter()
+            .find(|d| d.matches(group, ty))
+            .map(|d| d.decode(data))
+    }
+}
+
+/// Returns the first `count` items of `items`
+fn valid<T>(items: &[T], count: usize) -> Result<&[T], ApobError> {
+    items.get(..count).ok_or(ApobError::InvalidCount {
+        count,
+        max: items.len(),
+    })
+}
+
+struct EventLog;
+
+impl ApobDecoder for EventLog {
+    fn name(&self) -> &'static str {
+        "Event log"
+    }
+    fn matches(&self, group: ApobGroup, ty: u32) -> bool {
+        group == ApobGroup::GENERAL
+            && ty == apob::ApobGeneralType::EVENT_LOG as u32
+    }
+    fn decode<'a>(&self, data: &'a [u8]) -> DecodedSection<'a> {
+        apob::event_log(data)
+            .map_or_else(DecodedSection::Invalid, DecodedSection::EventLog)
+    }
+}
+
+struct MemMap;
+
+impl ApobDecoder for MemMap {
+    fn name(&self) -> &'static str {
+        "System memory map"
+    }
+    fn matches(&self, group: ApobGroup, ty: u32) -> bool {
+        group == ApobGroup::FABRIC
+            && ty == apob::ApobFabricType::SYS_MEM_MAP as u32
+    }
+    fn decode<'a>(&self, data: &'a [u8]) -> DecodedSection<'a> {
+        let out = apob::parse_prefix::<apob::ApobSysMemMap>(data).and_then(
+            |(map, rest)| {
+                let holes = <[apob::ApobSysMemMapHole]>::ref_from_prefix(rest)
+                    .map(|(h, _)| h)
+                    .unwrap_or_default();
+                let holes = valid(holes, map.hole_count as usize)?;
+                Ok(DecodedSection::MemMap {
+                    high_phys: map.high_phys,
+                    usable: map.usable_bytes(holes),
+                    holes,
+                })
+            },
+        );
+        out.unwrap_or_else(DecodedSection::Invalid)
+    }
+}
+
+struct PhyOverride;
+
+impl ApobDecoder for PhyOverride {
+    fn name(&self) -> &'static str {
+        "Milan PHY override"
+    }
+    fn matches(&self, group: ApobGroup, ty: u32) -> bool {
+        group == ApobGroup::FABRIC
+            && ty == apob::ApobFabricType::MILAN_FABRIC_PHY_OVERRIDE as u32
+    }
+    fn decode<'a>(&self, data: &'a [u8]) -> DecodedSection<'a> {
+        apob::payload_as::<apob::MilanApobPhyOverride>(data)
+            .map_or_else(DecodedSection::Invalid, DecodedSection::PhyOverride)
+    }
+}
+
+struct PmuTrainFail;
+
+impl ApobDecoder for PmuTrainFail {
+    fn name(&self) -> &'static str {
+        "Milan PMU training failures"
+    }
+    fn matches(&self, group: ApobGroup, ty: u32) -> bool {
+        group == ApobGroup::MEMORY
+            && ty == apob::ApobMemoryType::MILAN_PMU_TRAIN_FAIL as u32
+    }
+    fn decode<'a>(&self, data: &'a [u8]) -> DecodedSection<'a> {
+        apob::payload_as::<apob::PmuTfi>(data)
+            .and_then(|p| valid(&p.entries, p.nvalid as usize))
+            .map_or_else(DecodedSection::Invalid, DecodedSection::PmuTrainFail)
+    }
+}
+
+struct Coremap;
+
+impl ApobDecoder for Coremap {
+    fn name(&self) -> &'static str {
+        "Milan core map"
+    }
+    fn matches(&self, group: ApobGroup, ty: u32) -> bool {
+        group == ApobGroup::CCX && ty == apob::ApobCcxType::MILAN_COREMAP as u32
+    }
+    fn decode<'a>(&self, data: &'a [u8]) -> DecodedSection<'a> {
+        apob::payload_as::<apob::MilanApobCoremap>(data)
+            .map_or_else(DecodedSection::Invalid, DecodedSection::Coremap)
+    }
+}
+
+struct Smbios;
+
+impl ApobDecoder for Smbios {
+    fn name(&self) -> &'static str {
+        "SMBIOS table"
+    }
+    /// Types within the group aren't enumerated, so any entry is tried
+    fn matches(&self, group: ApobGroup, _ty: u32) -> bool {
+        group == ApobGroup::SMBIOS
+    }
+    fn decode<'a>(&self, data: &'a [u8]) -> DecodedSection<'a> {
+        apob::SmbiosStructures::new(data)
+            .collect::<Result<_, _>>()
+            .map_or_else(DecodedSection::Invalid, DecodedSection::Smbios)
+    }
+}
+
+struct Apcb;
+
+impl ApobDecoder for Apcb {
+    fn name(&self) -> &'static str {
+        "Embedded APCB"
+    }
+    /// Any entry in the group may hold an APCB, which is found by its
+    /// signature rather than its type
+    fn matches(&self, group: ApobGroup, _ty: u32) -> bool {
+        group == ApobGroup::APCB
+    }
+    fn decode<'a>(&self, data: &'a [u8]) -> DecodedSection<'a> {
+        DecodedSection::Apcb {
+            header: apob::ApcbHeader::parse(data),
+            len: data.len(),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Comparison of the entries in two APOB files
+
+use crate::{decode, json_item, Entry, Item};
+
+use serde_json::Value;
+use std::{collections::BTreeMap, io::Write};
+
+/// Entries are matched by `(group, type, instance, occurrence)`
+type Key = (u32, u32, u32, usize);
+
+fn keyed(entries: &[Entry]) -> Vec<(Key, &apob::ApobEntry, &Entry)> {
+    let mut seen = BTreeMap::new();
+    entries
+        .iter()
+        .filter_map(|item| {
+            let Item::Entry(e) = &item.entry else {
+                return None;
+            };
+            let k = (e.raw_group(), e.type_id(), e.inst);
+            let n = seen.entry(k).or_insert(0);
+            let key = (k.0, k.1, k.2, *n);
+            *n += 1;
+            Some((key, e, item))
+        })
+        .collect()
+}
+
+fn label(e: &apob::ApobEntry, item: &Entry) -> String {
+    let group = match e.group() {
+        Some(g) => apob::group_name(g).to_owned(),
+        None => format!("{:#x}", e.raw_group()),
+    };
+    format!(
+        "{group}/{:#x}/{:#x} @ {:#07x}",
+        e.type_id(),
+        { e.inst },
+        item.offset
+    )
+}
+
+/// Prints the differences between two sets of entries
+///
+/// Returns `true` if any differences were found
+pub fn diff<W: Write>(
+    out: &mut W,
+    a: &[Entry],
+    b: &[Entry],
+    decoders: &decode::Registry,
+) -> Result<bool, std::io::Error> {
+    let a = keyed(a);
+    let b = keyed(b);
+    let b_map = b
+        .iter()
+        .map(|(k, e, item)| (*k, (*e, *item)))
+        .collect::<BTreeMap<_, _>>();
+    let mut changed = false;
+
+    for (k, ea, ia) in &a {
+        let Some((eb, ib)) = b_map.get(k) else {
+            writeln!(out, "only in A: {}", label(ea, ia))?;
+            changed = true;
+            continue;
+        };
+        if ea.cancelled() == eb.cancelled() && ia.data() == ib.data() {
+            continue;
+        }
+        changed = true;
+        writeln!(out, "differs:   {} -> {}", label(ea, ia), label(eb, ib))?;
+        if ea.cancelled() != eb.cancelled() {
+            writeln!(
+                out,
+                "    cancelled: {} -> {}",
+                ea.cancelled(),
+                eb.cancelled()
+            )?;
+        }
+        if ia.data() == ib.data() {
+            continue;
+        }
+        let ja = json_item(ia.offset, ea, ia.data(), decoders);
+        let jb = json_item(ib.offset, eb, ib.data(), decoders);
+        match (ja.get("decoded"), jb.get("decoded")) {
+            (Some(da), Some(db)) => diff_json(out, "", da, db)?,
+            _ => diff_bytes(out, ia.data(), ib.data())?,
+        }
+    }
+    for (k, eb, ib) in &b {
+        if !a.iter().any(|(ka, ..)| ka == k) {
+            writeln!(out, "only in B: {}", label(eb, ib))?;
+            changed = true;
+        }
+    }
+    Ok(changed)
+}
+
+/// Prints each byte which differs, formatted like [`crate::print_hex`]
+fn diff_bytes<W: Write>(
+    out: &mut W,
+    a: &[u8],
+    b: &[u8],
+) -> Result<(), std::io::Error> {
+    writeln!(out, "            A  B")?;
+    let fmt = |v: Option<&u8>| match v {
+        Some(v) => format!("{v:02x}"),
+        None => "--".to_owned(),
+    };
+    for i in 0..a.len().max(b.len()) {
+        let (va, vb) = (a.get(i), b.get(i));
+        if va != vb {
+            writeln!(out, "    {i:04x} |  {} {}", fmt(va), fmt(vb))?;
+        }
+    }
+    Ok(())
+}
+
+/// Recursively prints each field which differs between two decoded values
+fn diff_json<W: Write>(
+    out: &mut W,
+    path: &str,
+    a: &Value,
+    b: &Value,
+) -> Result<(), std::io::Error> {
+    match (a, b) {
+        (Value::Object(oa), Value::Object(ob)) => {
+            for (k, va) in oa {
+                let vb = ob.get(k).unwrap_or(&Value::Null);
+                let path = if path.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{path}.{k}")
+                };
+                diff_json(out, &path, va, vb)?;
+            }
+            for (k, vb) in ob.iter().filter(|(k, _)| !oa.contains_key(*k)) {
+                diff_json(out, &format!("{path}.{k}"), &Value::Null, vb)?;
+            }
+        }
+        (Value::Array(aa), Value::Array(ab)) => {
+            for i in 0..aa.len().max(ab.len()) {
+                let va = aa.get(i).unwrap_or(&Value::Null);
+                let vb = ab.get(i).unwrap_or(&Value::Null);
+                diff_json(out, &format!("{path}[{i}]"), va, vb)?;
+            }
+        }
+        _ if a != b => writeln!(out, "    {path}: {a} -> {b}")?,
+        _ => (),
+    }
+    Ok(())
+}
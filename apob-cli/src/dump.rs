@@ -0,0 +1,82 @@
+//! Bulk export of entry payloads, for archiving a blob as separate files
+
+use crate::{Entry, Item};
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+/// Name of the file describing every exported entry
+const MANIFEST: &str = "manifest.json";
+
+/// Returns the name of the file holding an entry's payload
+fn file_name(offset: usize, e: &apob::ApobEntry) -> String {
+    let group = match e.group() {
+        Some(g) => apob::group_name(g).to_owned(),
+        None => format!("{:x}", e.raw_group()),
+    };
+    format!("{offset:05x}_{group}_{:x}_{:x}.bin", e.type_id(), {
+        e.inst
+    })
+}
+
+/// Writes each entry's payload to its own file in `dir`, plus a manifest
+///
+/// Nothing is written if any of the files already exist, unless `force` is
+/// set.  Returns the number of entries written.
+pub fn dump(
+    entries: &[Entry],
+    dir: &Path,
+    force: bool,
+    include_cancelled: bool,
+) -> Result<usize> {
+    let entries = entries
+        .iter()
+        .filter_map(|item| match &item.entry {
+            Item::Entry(e) if include_cancelled || !e.cancelled() => {
+                Some((file_name(item.offset, e), e, item))
+            }
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if !force {
+        let names = entries.iter().map(|(name, ..)| name.as_str());
+        if let Some(name) = std::iter::once(MANIFEST)
+            .chain(names)
+            .find(|name| dir.join(name).exists())
+        {
+            bail!(
+                "{:?} already exists; use --force to overwrite",
+                dir.join(name)
+            );
+        }
+    }
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("failed to create {dir:?}"))?;
+
+    let mut manifest = vec![];
+    for (name, e, item) in &entries {
+        let path = dir.join(name);
+        std::fs::write(&path, item.data())
+            .with_context(|| format!("failed to write {path:?}"))?;
+        manifest.push(serde_json::json!({
+            "file": name,
+            "offset": item.offset,
+            "group": e.group().map(apob::group_name),
+            "group_id": e.raw_group(),
+            "type": e.type_id(),
+            "type_name": e
+                .group()
+                .and_then(|g| apob::type_name(g, e.type_id())),
+            "instance": ({ e.inst }),
+            "cancelled": e.cancelled(),
+            "size": item.data().len(),
+        }));
+    }
+    let path = dir.join(MANIFEST);
+    let mut out = serde_json::to_string_pretty(&manifest)?;
+    out.push('\n');
+    std::fs::write(&path, out)
+        .with_context(|| format!("failed to write {path:?}"))?;
+    Ok(entries.len())
+}
@@ -1,28 +1,247 @@
-use anyhow::{Context, Result};
-use clap::Parser;
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use decode::DecodedSection;
 use std::{
-    io::{Read, Write},
-    path::PathBuf,
+    io::{IsTerminal, Read, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    rc::Rc,
 };
-use zerocopy::FromBytes;
 
 mod app;
+mod color;
+mod decode;
+mod diff;
+mod dump;
+mod overlay;
+mod schema;
+mod state;
+mod validate;
 
 /// Simple CLI to investigate an APOB file
-#[derive(Parser, Debug)]
+#[derive(Parser, Clone, Debug)]
 #[command(version, about, long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
     /// Prints raw data contents of all sections
     #[clap(short, long)]
     raw: bool,
+    /// Prints each entry's header (including its HMAC) as well as its data
+    #[clap(long, conflicts_with = "raw")]
+    raw_full: bool,
+    /// Prints raw data contents of only the entries which no decoder handles,
+    /// hiding the rest
+    #[clap(
+        long,
+        conflicts_with_all = ["interactive", "raw", "raw_full", "count"]
+    )]
+    raw_only_unknown: bool,
+    /// Labels raw output with absolute file offsets
+    #[clap(long)]
+    abs_offsets: bool,
+    /// Bytes per row in raw output (8, 16, or 32)
+    #[clap(long, default_value_t = 16, value_parser = parse_width)]
+    width: usize,
     /// Decodes known section types
     #[clap(short, long)]
     decode: bool,
+    /// Lists runs of printable text in each entry's data, like `strings`
+    #[clap(long)]
+    strings: bool,
+    /// Also shows sizes in human-readable units
+    #[clap(long)]
+    human: bool,
+    /// Only shows event log entries at or above the given class when decoding
+    #[clap(long, value_parser = parse_event_class)]
+    min_severity: Option<apob::MilanApobEventClass>,
+    /// When to color plain output
+    #[clap(long, value_enum, default_value_t = color::ColorChoice::Auto)]
+    color: color::ColorChoice,
+    /// Pipes output through `$PAGER` (or `less`) when writing to a terminal
+    #[clap(long, conflicts_with = "interactive")]
+    pager: bool,
+    /// TOML file which overrides the colors of groups and event classes
+    #[clap(long)]
+    theme: Option<PathBuf>,
     /// Runs an interactive viewer
     #[clap(short, long)]
     interactive: bool,
-    /// Name of the file to load
-    name: PathBuf,
+    /// Prints the parsed blob as a JSON document
+    #[clap(long, conflicts_with = "interactive")]
+    json: bool,
+    /// Prints per-group statistics and sanity checks instead of entries
+    #[clap(long, conflicts_with_all = ["interactive", "json"])]
+    summary: bool,
+    /// Prints one line per entry, for use with `grep` and `diff`
+    #[clap(long, conflicts_with_all = ["interactive", "json", "summary"])]
+    short: bool,
+    /// Prints the number of entries which pass the filters, instead of
+    /// listing them
+    #[clap(
+        long,
+        conflicts_with_all = ["interactive", "json", "summary", "short"]
+    )]
+    count: bool,
+    /// Only shows entries in the given group (may be repeated)
+    #[clap(short, long, value_parser = parse_group)]
+    group: Vec<apob::ApobGroup>,
+    /// Only shows entries of the given type (decimal or `0x`-prefixed hex),
+    /// within the groups given by `--group` (may be repeated)
+    #[clap(short = 't', long = "type", value_parser = parse_int)]
+    ty: Vec<u32>,
+    /// Only shows and decodes entries of the given `GROUP/TYPE`, where the
+    /// type is a number or a name such as `FABRIC/SYS_MEM_MAP` (may be
+    /// repeated)
+    #[clap(long, value_name = "GROUP/TYPE", value_parser = parse_group_type)]
+    decode_only: Vec<(apob::ApobGroup, u32)>,
+    /// Only shows the entry at this index, counting from 0 in file order
+    /// (including cancelled entries)
+    #[clap(long, conflicts_with_all = ["interactive", "count"])]
+    entry: Option<usize>,
+    /// Stops after listing this many entries (after filtering and sorting)
+    #[clap(long, conflicts_with_all = ["interactive", "summary"])]
+    limit: Option<usize>,
+    /// Order in which to list entries
+    #[clap(long, value_enum, default_value_t = SortOrder::Offset)]
+    sort: SortOrder,
+    /// Hides cancelled entries
+    #[clap(long, conflicts_with = "only_cancelled")]
+    no_cancelled: bool,
+    /// Only shows cancelled entries
+    #[clap(long)]
+    only_cancelled: bool,
+    /// Hides the blob header from the listing and the interactive viewer
+    #[clap(long)]
+    no_header: bool,
+    /// Hides the padding between the header and the first entry, and any
+    /// warning about it, from the listing and the interactive viewer
+    #[clap(long)]
+    no_padding: bool,
+    /// Parses the file even if its version is unsupported, in which case
+    /// entries may be misread
+    #[clap(long)]
+    version_override: bool,
+    /// Exits with an error if entries are not laid out end-to-end
+    #[clap(long)]
+    strict: bool,
+    /// Reads the blob starting at this byte of the file (decimal or
+    /// `0x`-prefixed hex), such as within a flash dump; offsets shown are
+    /// then relative to the blob
+    #[clap(long, value_parser = parse_int)]
+    offset: Option<u32>,
+    /// Reads the first blob found in the file, like `--offset` with the
+    /// position of the first signature followed by a valid header
+    #[clap(long, conflicts_with = "offset")]
+    scan: bool,
+    /// Lists the offset of every signature in the file, for use with
+    /// `--offset`, instead of reading a blob
+    #[clap(long, conflicts_with_all = ["offset", "scan", "interactive"])]
+    scan_all: bool,
+    /// Selects one image (counting from 0) from a file containing several
+    /// concatenated APOBs, such as a capture from each socket
+    #[clap(long)]
+    image: Option<usize>,
+    /// Reloads the file in the interactive viewer whenever it changes
+    #[clap(long, requires = "interactive")]
+    follow: bool,
+    /// File containing the key used to check entry HMACs in the interactive
+    /// viewer
+    #[cfg(feature = "hmac")]
+    #[clap(long, requires = "interactive")]
+    hmac_key: Option<PathBuf>,
+    /// Logs problems found while parsing to stderr; repeat to log every step
+    #[clap(short, long, action = clap::ArgAction::Count)]
+    #[clap(conflicts_with = "interactive")]
+    verbose: u8,
+    /// Don't restore (or save) the interactive viewer's state
+    #[clap(long)]
+    no_restore: bool,
+    /// Name of the file to load, or `-` to read from stdin
+    #[clap(required = true)]
+    name: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Clone, Debug)]
+enum Command {
+    /// Compares two APOB files, exiting with an error if they differ
+    Diff {
+        /// Original file
+        a: PathBuf,
+        /// New file
+        b: PathBuf,
+    },
+    /// Writes the payload of a single entry to a file
+    Extract {
+        /// Group of the entry
+        #[clap(short, long, value_parser = parse_group)]
+        group: apob::ApobGroup,
+        /// Type of the entry (decimal or `0x`-prefixed hex)
+        #[clap(short, long, value_parser = parse_int)]
+        r#type: u32,
+        /// Instance of the entry, required if several entries match
+        #[clap(short, long, value_parser = parse_int)]
+        instance: Option<u32>,
+        /// Output file, or `-` for stdout
+        #[clap(short, long)]
+        output: PathBuf,
+        /// Name of the file to load, or `-` to read from stdin
+        name: PathBuf,
+    },
+    /// Decodes a bare entry payload, such as one written by `extract`
+    DecodeSection {
+        /// Group of the payload
+        #[clap(short, long, value_parser = parse_group)]
+        group: apob::ApobGroup,
+        /// Type of the payload (decimal or `0x`-prefixed hex)
+        #[clap(short, long, value_parser = parse_int)]
+        r#type: u32,
+        /// Name of the file to load, or `-` to read from stdin
+        name: PathBuf,
+    },
+    /// Writes the payload of every entry to its own file, along with a
+    /// `manifest.json` describing them
+    Dump {
+        /// Directory in which to write files, which is created if needed
+        #[clap(long)]
+        out_dir: PathBuf,
+        /// Overwrites existing files
+        #[clap(long)]
+        force: bool,
+        /// Also writes cancelled entries
+        #[clap(long)]
+        include_cancelled: bool,
+        /// Name of the file to load, or `-` to read from stdin
+        name: PathBuf,
+    },
+    /// Runs structural checks and prints a JSON report of any findings
+    ///
+    /// Exits with an error if there are any errors, or any warnings when
+    /// `--strict` is given.
+    Validate {
+        /// Also fails on warnings
+        #[clap(long)]
+        strict: bool,
+        /// Name of the file to load, or `-` to read from stdin
+        name: PathBuf,
+    },
+    /// Prints the known groups, types, and struct layouts as JSON, for use by
+    /// tools in other languages
+    #[command(hide = true)]
+    Schema,
+}
+
+/// Order in which to list entries
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum SortOrder {
+    /// Position in the file
+    Offset,
+    /// Group, then position in the file
+    Group,
+    /// Group, type, and instance, then position in the file
+    GroupTypeInstance,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -30,161 +249,1187 @@ enum Item {
     Header(apob::ApobHeader),
     Padding,
     Entry(apob::ApobEntry),
+    /// Data past the size declared in the header
+    Trailing,
+    /// An entry which can't be parsed, such as one whose `size` runs past the
+    /// end of the file, along with everything after it
+    Truncated(apob::ApobError),
 }
 
 struct Entry {
     offset: usize,
     entry: Item,
-    data: Vec<u8>,
+    /// Contents of the whole file, shared between items
+    file: Rc<Input>,
+    /// Range of this item's data within `file`
+    range: Range<usize>,
+}
+
+impl Entry {
+    /// Returns this item's data, excluding any entry header
+    fn data(&self) -> &[u8] {
+        &self.file[self.range.clone()]
+    }
+
+    /// Returns this item's bytes, including any entry header
+    fn bytes(&self) -> &[u8] {
+        &self.file[self.offset..self.range.end]
+    }
+
+    /// Returns the size of this item in the file, including any header
+    fn size(&self) -> usize {
+        match &self.entry {
+            Item::Entry(e) => e.size as usize,
+            Item::Header(..)
+            | Item::Padding
+            | Item::Trailing
+            | Item::Truncated(..) => self.range.len(),
+        }
+    }
+
+    /// Returns a key for sorting items in the given order
+    ///
+    /// The header and padding always sort first, and trailing data (or a
+    /// truncated entry) last;
+    /// items with equal keys should be left in file order by using a stable
+    /// sort.
+    fn sort_key(&self, order: SortOrder) -> Option<(u32, u32, u32)> {
+        let e = match &self.entry {
+            Item::Entry(e) => e,
+            Item::Header(..) | Item::Padding => return None,
+            Item::Trailing | Item::Truncated(..) => {
+                return Some((u32::MAX, u32::MAX, u32::MAX))
+            }
+        };
+        let group = e.raw_group();
+        let ty = e.type_id();
+        match order {
+            SortOrder::Offset => None,
+            SortOrder::Group => Some((group, 0, 0)),
+            SortOrder::GroupTypeInstance => Some((group, ty, e.inst)),
+        }
+    }
 }
 
 fn main() -> Result<()> {
+    // Exit quietly when a reader such as a pager or `head` stops early,
+    // rather than panicking on the next write to stdout
+    #[cfg(unix)]
+    // SAFETY: this only restores the default disposition, before any threads
+    // are started
+    unsafe {
+        libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+    }
+
     let args = Args::parse();
+    if let Some(code) = run_pager(&args)? {
+        std::process::exit(code);
+    }
+    if args.verbose > 0 {
+        use tracing_subscriber::filter::LevelFilter;
+        tracing_subscriber::fmt()
+            .with_max_level(if args.verbose == 1 {
+                LevelFilter::DEBUG
+            } else {
+                LevelFilter::TRACE
+            })
+            .with_writer(std::io::stderr)
+            .with_ansi(std::io::stderr().is_terminal())
+            .without_time()
+            .init();
+    }
+    if let Some(path) = &args.theme {
+        let (theme, warnings) = color::Theme::load(path)?;
+        for w in warnings {
+            eprintln!("warning: {path:?}: {w}");
+        }
+        theme.install();
+    }
 
-    let mut f = std::fs::File::open(&args.name)
-        .with_context(|| format!("failed to open {:?}", args.name))?;
-    let mut data = vec![];
-    f.read_to_end(&mut data).context("failed to read file")?;
-
-    let (header, _rest) = apob::ApobHeader::ref_from_prefix(&data).unwrap();
-    assert_eq!(header.sig, apob::APOB_SIG, "invalid signature");
-    assert_eq!(header.version, apob::APOB_VERSION, "invalid version");
-
-    let header_size = std::mem::size_of_val(header);
-    let mut entries = vec![
-        Entry {
-            offset: 0,
-            entry: Item::Header(*header),
-            data: data[..header_size].to_owned(),
-        },
-        Entry {
-            offset: header_size,
-            entry: Item::Padding,
-            data: data[header_size..header.offset as usize].to_owned(),
-        },
-    ];
-    let mut pos = header.offset as usize;
-    while pos < data.len() {
-        let (entry, _rest) =
-            apob::ApobEntry::ref_from_prefix(&data[pos..]).unwrap();
-        let entry_data =
-            &data[pos..][..entry.size as usize][std::mem::size_of_val(entry)..];
-        entries.push(Entry {
-            offset: pos,
-            entry: Item::Entry(*entry),
-            data: entry_data.to_vec(),
+    if let Some(Command::Diff { a, b }) = &args.command {
+        let a = load(&Rc::new(read_input(a)?), false)?;
+        let b = load(&Rc::new(read_input(b)?), false)?;
+        if diff::diff(&mut std::io::stdout(), &a, &b, &decode::Registry::new())?
+        {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    if let Some(Command::Extract {
+        group,
+        r#type,
+        instance,
+        output,
+        name,
+    }) = &args.command
+    {
+        let data = read_input(name)?;
+        let apob = apob::Apob::parse(&data)?;
+        let found = apob
+            .find(*group, *r#type, *instance)
+            .collect::<Result<Vec<_>, _>>()?;
+        let group = apob::group_name(*group);
+        let payload = match found.as_slice() {
+            [] => bail!("no entry matches {group}/{type:#x}"),
+            [(_, data)] => data,
+            _ => bail!(
+                "{} entries match {group}/{type:#x}; \
+                 use --instance to pick one",
+                found.len()
+            ),
+        };
+        if output.as_os_str() == "-" {
+            std::io::stdout().write_all(payload)?;
+        } else {
+            std::fs::write(output, payload)
+                .with_context(|| format!("failed to write {output:?}"))?;
+        }
+        return Ok(());
+    }
+    if let Some(Command::DecodeSection {
+        group,
+        r#type,
+        name,
+    }) = &args.command
+    {
+        let data = read_input(name)?;
+        // Decoders only look at the group and type, so the rest of this
+        // stand-in header doesn't matter
+        let entry = apob::ApobEntry {
+            group: *group as u32,
+            ty: *r#type,
+            inst: 0,
+            size: u32::try_from(
+                data.len() + std::mem::size_of::<apob::ApobEntry>(),
+            )
+            .unwrap_or(u32::MAX),
+            hmac: [0; 32],
+        };
+        let decoders = decode::Registry::new();
+        let group = apob::group_name(*group);
+        match decoders.decode(&entry, &data) {
+            None => bail!("no decoder for {group}/{type:#x}"),
+            Some(DecodedSection::Invalid(e)) => {
+                bail!("could not decode {group}/{type:#x}: {e}")
+            }
+            Some(..) => decode_item(
+                &mut std::io::stdout(),
+                &entry,
+                &data,
+                &decoders,
+                false,
+                None,
+                color::ColorChoice::Auto.enabled(),
+            )?,
+        }
+        return Ok(());
+    }
+    if let Some(Command::Dump {
+        out_dir,
+        force,
+        include_cancelled,
+        name,
+    }) = &args.command
+    {
+        let entries = load(&Rc::new(read_input(name)?), false)?;
+        let n = dump::dump(&entries, out_dir, *force, *include_cancelled)?;
+        println!("wrote {n} entries to {out_dir:?}");
+        return Ok(());
+    }
+    if let Some(Command::Schema) = &args.command {
+        serde_json::to_writer_pretty(std::io::stdout(), &schema::schema())?;
+        println!();
+        return Ok(());
+    }
+    if let Some(Command::Validate { strict, name }) = &args.command {
+        let data = read_input(name)?;
+        let findings = validate::validate(&data, &decode::Registry::new());
+        let count = |s| findings.iter().filter(|f| f.severity == s).count();
+        let errors = count(validate::Severity::Error);
+        let warnings = count(validate::Severity::Warning);
+        let out = serde_json::json!({
+            "file": name,
+            "errors": errors,
+            "warnings": warnings,
+            "findings": findings,
         });
-        pos += entry.size as usize;
+        serde_json::to_writer_pretty(std::io::stdout(), &out)?;
+        println!();
+        if errors > 0 || (*strict && warnings > 0) {
+            std::process::exit(1);
+        }
+        return Ok(());
     }
 
+    let name = args.name.as_ref().unwrap();
+    if args.follow && name == Path::new("-") {
+        bail!("--follow needs a file, not stdin");
+    }
+    if args.scan_all {
+        let data = read_input(name)?;
+        for offset in signatures(&data) {
+            let rest = &data[offset..];
+            match apob::Apob::parse_any_version(rest) {
+                Ok(apob) => println!(
+                    "{offset:#x}  version {:#x}  size {:#x}",
+                    { apob.header().version },
+                    { apob.header().size }
+                ),
+                Err(e) => println!("{offset:#x}  invalid header: {e}"),
+            }
+        }
+        return Ok(());
+    }
+    let data = open(&args, name)?;
+    let apob = if args.version_override {
+        apob::Apob::parse_any_version(&data)?
+    } else {
+        match apob::Apob::parse(&data) {
+            Err(e @ apob::ApobError::BadVersion(..)) => {
+                bail!("{e}; use --version-override to parse it anyway")
+            }
+            r => r?,
+        }
+    };
+    let header = apob.header();
+    if header.version != apob::APOB_VERSION {
+        eprintln!(
+            "WARNING: version {:#x} is not supported (expected {:#x}); \
+             entries may not match their expected layout",
+            { header.version },
+            apob::APOB_VERSION
+        );
+    }
+    if args.count {
+        println!("{}", count(&args, &apob)?);
+        return Ok(());
+    }
+    // The viewer loads entries as it runs, so that huge blobs show up quickly
+    let mut entries = if args.interactive {
+        vec![]
+    } else {
+        load(&data, args.version_override)?
+    };
+    let decoders = decode::Registry::new();
+    if let Some(n) = args.entry {
+        let positions = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| matches!(item.entry, Item::Entry(..)))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        let Some(&pos) = positions.get(n) else {
+            bail!("no entry {n}; the blob has {} entries", positions.len());
+        };
+        entries.truncate(pos + 1);
+        entries.drain(..pos);
+    }
+    filter(&args, &mut entries);
+    if args.raw_only_unknown {
+        entries.retain(|item| match &item.entry {
+            Item::Entry(entry) => !decodable(&decoders, entry, item.data()),
+            _ => true,
+        });
+    }
+
+    // The viewer sorts its own rows, so that saved item indices stay valid
+    if !args.interactive {
+        entries.sort_by_key(|item| item.sort_key(args.sort));
+    }
+    let omitted = args.limit.map_or(0, |n| limit(&mut entries, n));
+
     if args.interactive {
+        let state_path = (!args.no_restore)
+            .then(|| state::path(name, &data))
+            .flatten();
+        let view = state_path.as_deref().and_then(state::load);
         let terminal = ratatui::init();
-        let app = app::App::new(entries);
-        app.run(terminal);
+        let mut app = app::App::new(
+            app::FileInfo {
+                name: if name == Path::new("-") {
+                    "<stdin>".to_owned()
+                } else {
+                    name.display().to_string()
+                },
+                size: data.len(),
+            },
+            entries,
+            decoders,
+            None,
+        );
+        app.set_sort(args.sort);
+        let filter_args = args.clone();
+        app.load_lazily(
+            Box::new(Loader::new(&data, args.version_override)?.filter(
+                move |item| match item {
+                    Ok(item) => keep(&filter_args, item),
+                    Err(_) => true,
+                },
+            )),
+            view,
+        );
+        #[cfg(feature = "hmac")]
+        if let Some(path) = &args.hmac_key {
+            let key = std::fs::read(path)
+                .with_context(|| format!("failed to read {path:?}"))?;
+            app.set_hmac_key(key);
+        }
+        if args.follow {
+            let path = name.clone();
+            let args = args.clone();
+            app.follow(app::Follow::new(
+                path.clone(),
+                Box::new(move || {
+                    let mut entries =
+                        load(&open(&args, &path)?, args.version_override)?;
+                    filter(&args, &mut entries);
+                    Ok(entries)
+                }),
+            ));
+        }
+        let view = app.run(terminal);
         ratatui::restore();
+        if let Some(p) = state_path {
+            state::save(&p, &view);
+        }
+    } else if args.summary {
+        print_summary(&mut std::io::stdout(), &apob, &entries, args.human)?;
+    } else if args.short {
+        for item in &entries {
+            match &item.entry {
+                Item::Entry(entry) => println!(
+                    "{}",
+                    short_line(item.offset, entry, item.data(), &decoders)
+                ),
+                Item::Truncated(e) => {
+                    println!("TRUNCATED @{:#07x} {e}", item.offset)
+                }
+                _ => (),
+            }
+        }
+        if omitted > 0 {
+            println!("{}", more_entries(omitted));
+        }
+    } else if args.json {
+        let items = entries
+            .iter()
+            .filter_map(|item| match &item.entry {
+                Item::Entry(entry) => {
+                    Some(json_item(item.offset, entry, item.data(), &decoders))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let mut out = serde_json::json!({
+            "header": &header,
+            "entries": items,
+        });
+        if let Some((offset, e)) =
+            entries.iter().find_map(|item| match &item.entry {
+                Item::Truncated(e) => Some((item.offset, e)),
+                _ => None,
+            })
+        {
+            out["truncated"] = serde_json::json!({
+                "offset": offset,
+                "error": e.to_string(),
+            });
+        }
+        serde_json::to_writer_pretty(std::io::stdout(), &out)?;
+        println!();
+        // Keep stdout valid JSON
+        if omitted > 0 {
+            eprintln!("{}", more_entries(omitted));
+        }
     } else {
-        println!("{header:?}");
+        let color = args.color.enabled();
+        if !args.no_header {
+            println!("{header:?}");
+        }
+        if !args.no_padding && !apob.padding_is_zero() {
+            println!("warning: padding contains non-zero bytes");
+        }
+        let images = apob::MultiApob::new(&data).count();
+        if args.image.is_none() && images > 1 {
+            println!(
+                "warning: file contains {images} images; \
+                 use --image to select one"
+            );
+        }
+        if let Err(errs) = apob.validate_layout() {
+            for e in errs {
+                println!("warning: {e}");
+            }
+        }
         println!(
             "{:<7}   {:<8}   {:>4}   {:>8}   {:>9}",
             "OFFSET", "GROUP", "TYPE", "INSTANCE", "DATA SIZE"
         );
         for item in &entries {
-            let Item::Entry(entry) = &item.entry else {
-                continue;
+            let entry = match &item.entry {
+                Item::Entry(entry) => entry,
+                Item::Truncated(e) => {
+                    let line =
+                        format!("{:#07x}   TRUNCATED ENTRY: {e}", item.offset);
+                    println!(
+                        "{}",
+                        color::paint(&line, ratatui::style::Color::Red, color)
+                    );
+                    continue;
+                }
+                _ => continue,
+            };
+            // Unknown groups have no color, so are shown plainly
+            let group = match entry.group() {
+                Some(g) => color::paint(
+                    &format!("{:<8}", apob::group_name(g)),
+                    color::group(g),
+                    color,
+                ),
+                None => format!("{:<#8x}", entry.raw_group()),
             };
             println!(
-                "{:#07x}   {:<8}   {:>4x}   {:>8x}   {:>9x}",
+                "{:#07x}   {group}   {:>4x}   {:>8x}   {:>9x}",
                 item.offset,
-                format!("{:?}", entry.group().unwrap()),
-                entry.ty & !apob::APOB_CANCELLED,
-                entry.inst,
+                entry.type_id(),
+                { entry.inst },
                 entry.size as usize - std::mem::size_of_val(entry)
             );
-            if args.raw {
-                print_hex(&mut std::io::stdout(), &item.data).unwrap();
+            if args.raw || args.raw_only_unknown {
+                let base = if args.abs_offsets {
+                    item.offset + std::mem::size_of_val(entry)
+                } else {
+                    0
+                };
+                print_hex(
+                    &mut std::io::stdout(),
+                    item.data(),
+                    base,
+                    args.width,
+                )
+                .unwrap();
+            }
+            if args.raw_full {
+                let base = if args.abs_offsets { item.offset } else { 0 };
+                let (header, data) =
+                    item.bytes().split_at(std::mem::size_of_val(entry));
+                println!(
+                    "    entry header ({:#x} bytes, HMAC at {:#x}):",
+                    header.len(),
+                    base + std::mem::offset_of!(apob::ApobEntry, hmac)
+                );
+                print_hex(&mut std::io::stdout(), header, base, args.width)
+                    .unwrap();
+                println!("    payload ({:#x} bytes):", data.len());
+                print_hex(
+                    &mut std::io::stdout(),
+                    data,
+                    base + header.len(),
+                    args.width,
+                )
+                .unwrap();
+            }
+            if args.decode || !args.decode_only.is_empty() {
+                decode_item(
+                    &mut std::io::stdout(),
+                    entry,
+                    item.data(),
+                    &decoders,
+                    args.human,
+                    args.min_severity,
+                    color,
+                )
+                .unwrap();
+            }
+            if args.strings {
+                let base = if args.abs_offsets {
+                    item.offset + std::mem::size_of_val(entry)
+                } else {
+                    0
+                };
+                let strings = decode::extract_strings(
+                    item.data(),
+                    decode::MIN_STRING_LEN,
+                );
+                for (offset, s) in strings {
+                    println!("    {:06x}  {s}", base + offset);
+                }
+            }
+        }
+        if omitted > 0 {
+            println!("{}", more_entries(omitted));
+        }
+    }
+
+    if args.strict {
+        if let Err(errs) = apob.validate_layout() {
+            let errs = errs.iter().map(|e| e.to_string()).collect::<Vec<_>>();
+            bail!("invalid layout: {}", errs.join("; "));
+        }
+    }
+
+    Ok(())
+}
+
+/// Contents of an input file
+enum Input {
+    Owned(Vec<u8>),
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+impl std::ops::Deref for Input {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        match self {
+            Input::Owned(data) => data,
+            #[cfg(feature = "mmap")]
+            Input::Mapped(map) => map,
+        }
+    }
+}
+
+/// Reads a file, or stdin if the name is `-`
+///
+/// With the `mmap` feature, files are mapped rather than read, falling back
+/// to reading them if they can't be mapped.
+fn read_input(name: &Path) -> Result<Input> {
+    let mut data = vec![];
+    if name.as_os_str() == "-" {
+        std::io::stdin()
+            .read_to_end(&mut data)
+            .context("failed to read stdin")?;
+    } else {
+        let mut f = std::fs::File::open(name)
+            .with_context(|| format!("failed to open {name:?}"))?;
+        // SAFETY: the map is read-only, but another process could still
+        // modify the file while we're looking at it.  We accept that risk in
+        // a debugging tool, since the alternative is reading huge files.
+        #[cfg(feature = "mmap")]
+        if let Ok(map) = unsafe { memmap2::Mmap::map(&f) } {
+            return Ok(Input::Mapped(map));
+        }
+        f.read_to_end(&mut data).context("failed to read file")?;
+    }
+    Ok(Input::Owned(data))
+}
+
+/// Reads the input named on the command line, selecting an image if requested
+///
+/// Files which are being followed are always read rather than mapped, since
+/// they're expected to be rewritten underneath us.
+fn open(args: &Args, name: &Path) -> Result<Rc<Input>> {
+    let mut data = if args.follow {
+        Input::Owned(
+            std::fs::read(name)
+                .with_context(|| format!("failed to read {name:?}"))?,
+        )
+    } else {
+        read_input(name)?
+    };
+    let offset = if args.scan {
+        let check = |d: &[u8]| {
+            if args.version_override {
+                apob::Apob::parse_any_version(d).is_ok()
+            } else {
+                apob::Apob::parse(d).is_ok()
+            }
+        };
+        let offset = signatures(&data)
+            .find(|&o| check(&data[o..]))
+            .with_context(|| format!("no blob found in {name:?}"))?;
+        if !args.interactive {
+            eprintln!("found a blob at {offset:#x}");
+        }
+        Some(offset)
+    } else {
+        args.offset.map(|o| o as usize)
+    };
+    if let Some(offset) = offset {
+        data = Input::Owned(embedded(&data, offset).with_context(|| {
+            format!("failed to read a blob at {offset:#x} in {name:?}")
+        })?);
+    }
+    #[cfg(feature = "swap")]
+    if apob::Apob::parse(&data).err() == Some(apob::ApobError::ByteSwapped) {
+        // Printing would garble the interactive viewer, which may reopen the
+        // file while it's running
+        if !args.interactive {
+            eprintln!("warning: {name:?} is byte-swapped; swapping it back");
+        }
+        data = Input::Owned(apob::ApobOwned::from_swapped(&data)?.into_vec());
+    }
+    if let Some(n) = args.image {
+        let image = apob::MultiApob::new(&data)
+            .nth(n)
+            .with_context(|| format!("{name:?} has no image {n}"))?
+            .with_context(|| format!("failed to parse image {n}"))?;
+        data = Input::Owned(image.data().to_vec());
+    }
+    Ok(Rc::new(data))
+}
+
+/// Reruns this command with its output piped through a pager, if `--pager`
+/// was given and stdout is a terminal, returning the exit code to use
+///
+/// Like git, this sets `LESS=FRX` unless `LESS` is already set, so that `less`
+/// passes colors through and exits right away if everything fits on screen;
+/// colors are then kept for `less` but not for other pagers.
+fn run_pager(args: &Args) -> Result<Option<i32>> {
+    if !args.pager || !std::io::stdout().is_terminal() {
+        return Ok(None);
+    }
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "less".to_owned());
+    let mut words = pager.split_whitespace();
+    let program = words.next().unwrap();
+    let is_less = Path::new(program).file_name() == Some("less".as_ref());
+
+    let mut cmd = std::process::Command::new(std::env::current_exe()?);
+    cmd.args(std::env::args_os().skip(1).filter(|a| a != "--pager"))
+        .stdout(std::process::Stdio::piped());
+    if is_less && args.color == color::ColorChoice::Auto {
+        cmd.args(["--color", "always"]);
+    }
+    let mut child = cmd.spawn().context("failed to rerun for the pager")?;
+
+    let mut pager_cmd = std::process::Command::new(program);
+    pager_cmd.args(words).stdin(child.stdout.take().unwrap());
+    if std::env::var_os("LESS").is_none() {
+        pager_cmd.env("LESS", "FRX");
+    }
+    let status = pager_cmd
+        .status()
+        .with_context(|| format!("failed to run pager {pager:?}"))?;
+    // With no exit code, the child was killed by SIGPIPE because the pager
+    // quit early, which is fine
+    let code = child.wait()?.code().unwrap_or(0);
+    Ok(Some(if status.success() { code } else { 1 }))
+}
+
+/// Finds every occurrence of the APOB signature
+fn signatures(data: &[u8]) -> impl Iterator<Item = usize> + '_ {
+    data.windows(apob::APOB_SIG.len())
+        .enumerate()
+        .filter(|(_, w)| *w == apob::APOB_SIG)
+        .map(|(i, _)| i)
+}
+
+/// Copies out the blob which starts at `offset` in a larger image
+///
+/// The blob ends at the size declared in its header, or at the end of the
+/// image if that would run past it.
+fn embedded(data: &[u8], offset: usize) -> Result<Vec<u8>> {
+    let Some(rest) = data.get(offset..) else {
+        bail!(
+            "offset is past the end of the file ({:#x} bytes)",
+            data.len()
+        );
+    };
+    let sig = rest.get(..apob::APOB_SIG.len()).unwrap_or(rest);
+    if sig != apob::APOB_SIG && sig != apob::APOB_SIG_SWAPPED {
+        bail!("no APOB signature there (found {sig:02x?})");
+    }
+    let header = apob::ApobHeader::try_from(rest)?;
+    // A byte-swapped blob is swapped back later, but its size is needed now
+    let size = if sig == apob::APOB_SIG_SWAPPED {
+        header.size.swap_bytes()
+    } else {
+        header.size
+    };
+    let end = usize::try_from(size).unwrap_or(usize::MAX).min(rest.len());
+    Ok(rest[..end].to_vec())
+}
+
+/// Drops entries which don't match the `--group`, `--type`, `--decode-only`,
+/// and cancellation filters, along with any header and padding rows hidden
+/// by `--no-header` and `--no-padding`
+fn filter(args: &Args, entries: &mut Vec<Entry>) {
+    entries.retain(|item| keep(args, item));
+}
+
+/// Checks whether an item passes the filters; see [`filter`]
+fn keep(args: &Args, item: &Entry) -> bool {
+    match &item.entry {
+        Item::Entry(entry) => keep_entry(args, entry),
+        Item::Header(..) => args.decode_only.is_empty() && !args.no_header,
+        Item::Padding => args.decode_only.is_empty() && !args.no_padding,
+        Item::Trailing | Item::Truncated(..) => args.decode_only.is_empty(),
+    }
+}
+
+/// Checks whether an entry passes the filters
+fn keep_entry(args: &Args, entry: &apob::ApobEntry) -> bool {
+    let group_ok = args.group.is_empty()
+        || entry.group().is_some_and(|g| args.group.contains(&g));
+    let type_ok = args.ty.is_empty() || args.ty.contains(&entry.type_id());
+    let decode_only_ok = args.decode_only.is_empty()
+        || entry
+            .group()
+            .is_some_and(|g| args.decode_only.contains(&(g, entry.type_id())));
+    let cancel_ok = if entry.cancelled() {
+        !args.no_cancelled
+    } else {
+        !args.only_cancelled
+    };
+    group_ok && type_ok && decode_only_ok && cancel_ok
+}
+
+/// Checks whether a decoder describes an entry's payload
+fn decodable(
+    decoders: &decode::Registry,
+    entry: &apob::ApobEntry,
+    data: &[u8],
+) -> bool {
+    decoders.decode(entry, data).is_some()
+}
+
+/// Counts the entries which pass the filters, walking only their headers
+///
+/// As with [`load`], entries past the size declared in the header are
+/// ignored.
+fn count(args: &Args, apob: &apob::Apob) -> Result<usize> {
+    let declared = apob.header().size as usize;
+    let mut pos = apob.entries_offset();
+    let mut n = 0;
+    for entry in apob.iter_headers() {
+        if pos >= declared {
+            break;
+        }
+        let entry = entry?;
+        pos += entry.size as usize;
+        n += usize::from(keep_entry(args, entry));
+    }
+    Ok(n)
+}
+
+/// Keeps the first `n` entries, along with the header, padding, and trailing
+/// data, returning the number of entries dropped
+fn limit(entries: &mut Vec<Entry>, n: usize) -> usize {
+    let before = entries.len();
+    let mut seen = 0;
+    entries.retain(|item| match item.entry {
+        Item::Entry(..) => {
+            seen += 1;
+            seen <= n
+        }
+        _ => true,
+    });
+    before - entries.len()
+}
+
+/// Describes the entries dropped by [`limit`]
+fn more_entries(n: usize) -> String {
+    format!("... {n} more {}", if n == 1 { "entry" } else { "entries" })
+}
+
+/// Splits a blob into header, padding, entries, and trailing data
+///
+/// Entries are only parsed up to the size declared in the header; anything
+/// after that is returned as a single [`Item::Trailing`].  An entry which
+/// can't be parsed ends the list as an [`Item::Truncated`].  Items refer to
+/// ranges of `file` rather than copying their data.
+fn load(file: &Rc<Input>, any_version: bool) -> Result<Vec<Entry>> {
+    Loader::new(file, any_version)?.collect()
+}
+
+/// Incremental form of [`load`], which yields items as they're parsed
+struct Loader {
+    file: Rc<Input>,
+    /// Header and padding, in reverse order, which are yielded first
+    queue: Vec<Entry>,
+    /// Offset of the next entry, or `None` once entries are exhausted
+    pos: Option<usize>,
+    declared: usize,
+}
+
+impl Loader {
+    /// Starts loading `file`, which may have an unsupported version if
+    /// `any_version` is set
+    fn new(file: &Rc<Input>, any_version: bool) -> Result<Self> {
+        let apob = if any_version {
+            apob::Apob::parse_any_version(file)?
+        } else {
+            apob::Apob::parse(file)?
+        };
+        let header_size = std::mem::size_of::<apob::ApobHeader>();
+        let queue = vec![
+            Entry {
+                offset: header_size,
+                entry: Item::Padding,
+                file: file.clone(),
+                range: header_size..header_size + apob.padding().len(),
+            },
+            Entry {
+                offset: 0,
+                entry: Item::Header(*apob.header()),
+                file: file.clone(),
+                range: 0..header_size,
+            },
+        ];
+        Ok(Self {
+            file: file.clone(),
+            queue,
+            pos: Some(apob.entries_offset()),
+            declared: apob.header().size as usize,
+        })
+    }
+}
+
+impl Iterator for Loader {
+    type Item = Result<Entry>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.queue.pop() {
+            return Some(Ok(item));
+        }
+        let pos = self.pos?;
+        let file = self.file.clone();
+        // The header was checked in `Loader::new`, including its version if
+        // that was asked for
+        let apob = apob::Apob::parse_any_version(&file).unwrap();
+        let next = if pos < self.declared {
+            apob.entries_from(pos).next()
+        } else {
+            None
+        };
+        match next {
+            Some(Ok((entry, _))) => {
+                let size = entry.size as usize;
+                self.pos = Some(pos + size);
+                Some(Ok(Entry {
+                    offset: pos,
+                    entry: Item::Entry(*entry),
+                    file: file.clone(),
+                    range: pos + std::mem::size_of_val(entry)..pos + size,
+                }))
             }
-            if args.decode {
-                decode_item(&mut std::io::stdout(), entry, &item.data).unwrap();
+            // Keep what we've parsed so far, rather than failing outright
+            Some(Err(e)) => {
+                self.pos = None;
+                Some(Ok(Entry {
+                    offset: pos,
+                    entry: Item::Truncated(e),
+                    file: file.clone(),
+                    range: pos..file.len(),
+                }))
+            }
+            None => {
+                self.pos = None;
+                let end = pos.max(self.declared);
+                (end < file.len()).then(|| {
+                    Ok(Entry {
+                        offset: end,
+                        entry: Item::Trailing,
+                        file: file.clone(),
+                        range: end..file.len(),
+                    })
+                })
+            }
+        }
+    }
+}
+
+fn print_summary<W: Write>(
+    out: &mut W,
+    apob: &apob::Apob,
+    entries: &[Entry],
+    human: bool,
+) -> Result<(), std::io::Error> {
+    let header = apob.header();
+    #[derive(Default)]
+    struct Stats {
+        count: usize,
+        cancelled: usize,
+        bytes: usize,
+    }
+    let mut groups = std::collections::BTreeMap::new();
+    let mut warnings = vec![];
+    let mut end = 0;
+    for item in entries {
+        match &item.entry {
+            Item::Entry(entry) => {
+                let g = groups
+                    .entry(entry.raw_group())
+                    .or_insert_with(Stats::default);
+                g.count += 1;
+                g.cancelled += usize::from(entry.cancelled());
+                g.bytes += item.data().len();
             }
+            Item::Header(..) | Item::Padding => (),
+            Item::Trailing | Item::Truncated(..) => continue,
         }
+        end = end.max(item.offset + item.size());
+    }
+
+    writeln!(out, "version:       {:#x}", { header.version })?;
+    writeln!(out, "declared size: {:#x}", { header.size })?;
+    writeln!(out, "actual size:   {end:#x}")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{:<8}   {:>7}   {:>9}   {:>10}",
+        "GROUP", "ENTRIES", "CANCELLED", "DATA BYTES"
+    )?;
+    let mut total = Stats::default();
+    for (g, stats) in &groups {
+        let name = match apob::ApobGroup::from_repr(*g as usize) {
+            Some(g) => apob::group_name(g).to_owned(),
+            None => format!("{g:#x}"),
+        };
+        writeln!(
+            out,
+            "{name:<8}   {:>7}   {:>9}   {:>10x}{}",
+            stats.count,
+            stats.cancelled,
+            stats.bytes,
+            human_suffix(human, stats.bytes as u64),
+        )?;
+        total.count += stats.count;
+        total.cancelled += stats.cancelled;
+        total.bytes += stats.bytes;
     }
+    writeln!(
+        out,
+        "{:<8}   {:>7}   {:>9}   {:>10x}{}",
+        "TOTAL",
+        total.count,
+        total.cancelled,
+        total.bytes,
+        human_suffix(human, total.bytes as u64),
+    )?;
 
+    if !apob.padding_is_zero() {
+        warnings.push("padding contains non-zero bytes".to_owned());
+    }
+    if let Err(errs) = apob.validate_layout() {
+        warnings.extend(errs.iter().map(|e| e.to_string()));
+    }
+    if !warnings.is_empty() {
+        writeln!(out)?;
+        for w in &warnings {
+            writeln!(out, "warning: {w}")?;
+        }
+    }
     Ok(())
 }
 
+/// Formats a byte count using binary units, e.g. `2.0 GiB`
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut v = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while v >= 1024.0 && unit + 1 < UNITS.len() {
+        v /= 1024.0;
+        unit += 1;
+    }
+    format!("{v:.1} {}", UNITS[unit])
+}
+
+/// Returns ` (<human size>)` if `human` is set, or an empty string
+fn human_suffix(human: bool, bytes: u64) -> String {
+    if human {
+        format!(" ({})", human_size(bytes))
+    } else {
+        String::new()
+    }
+}
+
+fn parse_width(s: &str) -> Result<usize, String> {
+    match s.parse() {
+        Ok(w @ (8 | 16 | 32)) => Ok(w),
+        _ => Err("must be 8, 16, or 32".to_owned()),
+    }
+}
+
+fn parse_int(s: &str) -> Result<u32, std::num::ParseIntError> {
+    if let Some(hex) = s.strip_prefix("0x") {
+        u32::from_str_radix(hex, 16)
+    } else {
+        s.parse()
+    }
+}
+
+fn parse_event_class(s: &str) -> Result<apob::MilanApobEventClass, String> {
+    apob::MilanApobEventClass::from_name(s).ok_or_else(|| {
+        let names = (0..=u8::MAX as usize)
+            .filter_map(apob::MilanApobEventClass::from_repr)
+            .map(<&str>::from)
+            .collect::<Vec<_>>();
+        format!("unknown class; valid classes are {}", names.join(", "))
+    })
+}
+
+fn parse_group(s: &str) -> Result<apob::ApobGroup, String> {
+    apob::ApobGroup::from_name(s).ok_or_else(|| {
+        let names = apob::ApobGroup::iter()
+            .map(<&str>::from)
+            .collect::<Vec<_>>();
+        format!("unknown group; valid groups are {}", names.join(", "))
+    })
+}
+
+/// Parses `GROUP/TYPE`, where the type is a number or one of the group's
+/// type names
+fn parse_group_type(s: &str) -> Result<(apob::ApobGroup, u32), String> {
+    let (group, ty) = s
+        .split_once('/')
+        .ok_or_else(|| "expected GROUP/TYPE".to_owned())?;
+    let group = parse_group(group)?;
+    if let Ok(ty) = parse_int(ty) {
+        return Ok((group, ty));
+    }
+    let types = || {
+        (0..=u32::from(u8::MAX))
+            .filter_map(move |t| Some((t, apob::type_name(group, t)?)))
+    };
+    types()
+        .find(|(_, name)| name.eq_ignore_ascii_case(ty))
+        .map(|(t, _)| (group, t))
+        .ok_or_else(|| {
+            let names = types().map(|(_, name)| name).collect::<Vec<_>>();
+            format!(
+                "unknown type for {}; valid types are {}",
+                apob::group_name(group),
+                names.join(", ")
+            )
+        })
+}
+
 fn decode_item<W: Write>(
     out: &mut W,
     entry: &apob::ApobEntry,
     data: &[u8],
+    decoders: &decode::Registry,
+    human: bool,
+    min_severity: Option<apob::MilanApobEventClass>,
+    color: bool,
 ) -> Result<(), std::io::Error> {
-    let Some(group) = entry.group() else {
-        return Ok(());
-    };
-    match (group, entry.ty) {
-        (apob::ApobGroup::GENERAL, ty)
-            if ty == apob::ApobGeneralType::EVENT_LOG as u32 =>
-        {
+    match decoders.decode(entry, data) {
+        Some(section) => {
+            write_section(out, section, human, min_severity, color)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Writes the text form of a decoded section, as for `--decode`
+fn write_section<W: Write>(
+    out: &mut W,
+    section: DecodedSection,
+    human: bool,
+    min_severity: Option<apob::MilanApobEventClass>,
+    color: bool,
+) -> Result<(), std::io::Error> {
+    match section {
+        DecodedSection::EventLog(events) => {
             writeln!(out, "    Milan APOB event log")?;
+            let mut counts = (0..=u8::MAX as usize)
+                .filter_map(apob::MilanApobEventClass::from_repr)
+                .map(|c| {
+                    let n = events.iter().filter(|v| v.class() == Some(c));
+                    format!("{c:?} {}", n.count())
+                })
+                .collect::<Vec<_>>();
+            let other = events.iter().filter(|v| v.class().is_none()).count();
+            if other > 0 {
+                counts.push(format!("other {other}"));
+            }
+            writeln!(out, "    {}", counts.join(", "))?;
             writeln!(out, "    -------------------------------------")?;
             writeln!(
                 out,
                 "    INDEX   CLASS        EVENT                 DATA"
             )?;
-            let (log, _) =
-                apob::MilanApobEventLog::ref_from_prefix(data).unwrap();
-            for (i, v) in log.events[..log.count as usize].iter().enumerate() {
+            for (i, v) in events.iter().enumerate() {
+                if min_severity.is_some_and(|c| !v.is_at_least(c)) {
+                    continue;
+                }
+                let class = match v.class() {
+                    Some(c) => color::paint(
+                        &format!(
+                            "{:>12}",
+                            format!("{c:?} ({:#x})", { v.class })
+                        ),
+                        color::event_class(c),
+                        color,
+                    ),
+                    None => format!("{:>12}", format!("{:#x}", { v.class })),
+                };
                 writeln!(
                     out,
-                    "       {i:02x}  {:>12}  {:<20}  {:#x} {:#x}",
-                    if let Some(c) =
-                        apob::MilanApobEventClass::from_repr(v.class as usize)
-                    {
-                        format!("{c:?} ({:#x})", v.class)
-                    } else {
-                        format!("{:#x}", v.class)
-                    },
-                    if let Some(c) =
-                        apob::MilanApobEventInfo::from_repr(v.info as usize)
-                    {
-                        format!("{c:?} ({:#x})", v.info)
+                    "       {i:02x}  {class}  {:<20}  {:#x} {:#x}",
+                    if let Some(c) = apob::event_info_name(v.info) {
+                        format!("{c} ({:#x})", { v.info })
                     } else {
-                        format!("{:#x}", v.info)
+                        format!("{:#x}", { v.info })
                     },
-                    v.data0,
-                    v.data1
+                    { v.data0 },
+                    { v.data1 }
                 )?;
+                // Indent details so that they line up with the EVENT column
+                for line in apob::decode_event_data(v.info, v.data0, v.data1) {
+                    writeln!(out, "{:25}  {line}", "")?;
+                }
             }
         }
-        (apob::ApobGroup::FABRIC, ty)
-            if ty == apob::ApobFabricType::SYS_MEM_MAP as u32 =>
-        {
-            let (map, holes) =
-                apob::ApobSysMemMap::ref_from_prefix(data).unwrap();
+        DecodedSection::MemMap {
+            high_phys,
+            usable,
+            holes,
+        } => {
             writeln!(out, "    APOB fabric")?;
-            writeln!(out, "    high_phys: {:#10x}", map.high_phys)?;
+            writeln!(
+                out,
+                "    high_phys: {:#10x}{}",
+                high_phys,
+                human_suffix(human, high_phys)
+            )?;
+            writeln!(
+                out,
+                "    usable:    {usable:#10x} ({})",
+                human_size(usable)
+            )?;
             writeln!(out, "    -------------------------------------")?;
-            writeln!(out, "            BASE        SIZE  TYPE")?;
-            let holes =
-                <[apob::ApobSysMemMapHole]>::ref_from_bytes(holes).unwrap();
-            for h in &holes[..map.hole_count as usize] {
+            // Leave room for the longest suffix, e.g. " (1023.9 KiB)"
+            let w = if human { 23 } else { 10 };
+            writeln!(out, "            BASE  {:<w$}  TYPE", "      SIZE")?;
+            for h in holes {
+                let size = format!(
+                    "0x{:0>8x}{}",
+                    { h.size },
+                    human_suffix(human, h.size)
+                );
                 writeln!(
                     out,
-                    "    0x{:0>10x}  0x{:0>8x}  {:#04x}",
-                    h.base, h.size, h.ty
+                    "    0x{:0>10x}  {size:<w$}  {:#04x}",
+                    { h.base },
+                    { h.ty },
                 )?;
             }
         }
-        (apob::ApobGroup::MEMORY, ty)
-            if ty == apob::ApobMemoryType::MILAN_PMU_TRAIN_FAIL as u32 =>
-        {
-            let (p, _) = apob::PmuTfi::ref_from_prefix(data).unwrap();
+        DecodedSection::PhyOverride(p) => {
+            writeln!(out, "    Milan fabric PHY override")?;
+            writeln!(out, "    map_datalen: {:#x}", { p.map_datalen })?;
+            writeln!(out, "    -------------------------------------")?;
+            match p.data() {
+                Ok(d) => print_hex(out, d, 0, 16)?,
+                Err(e) => writeln!(out, "    invalid map_datalen: {e:?}")?,
+            }
+        }
+        DecodedSection::PmuTrainFail(entries) => {
             writeln!(out, "    PMU training failure log")?;
             writeln!(out, "    -------------------------------------")?;
             writeln!(
                 out,
                 "    INDEX  SOCK UMC   1D2D 1DNUM  STAGE  ERROR   DATA"
             )?;
-            for (i, h) in p.entries[..p.nvalid as usize].iter().enumerate() {
+            for (i, h) in entries.iter().enumerate() {
                 writeln!(
                     out,
                     "       {i:02x}  {:>4} {:>3}  {:>5} {:>5} {:>6}  {:x}  {:x} {:x} {:x} {:x}",
@@ -193,31 +1438,236 @@ fn decode_item<W: Write>(
                     h.bits.dimension(),
                     h.bits.num_1d(),
                     h.bits.stage(),
-                    h.error,
-                    h.data[0],
-                    h.data[1],
-                    h.data[2],
-                    h.data[3],
+                    { h.error },
+                    { h.data[0] },
+                    { h.data[1] },
+                    { h.data[2] },
+                    { h.data[3] },
+                )?;
+            }
+        }
+        DecodedSection::Apcb { header: None, .. } => {
+            writeln!(out, "    APCB")?;
+            writeln!(out, "    no embedded APCB (signature not found)")?;
+        }
+        DecodedSection::Apcb {
+            header: Some(h),
+            len,
+        } => {
+            writeln!(out, "    APCB")?;
+            writeln!(out, "    version:     {:#x}", { h.version })?;
+            writeln!(out, "    header_size: {:#x}", { h.header_size })?;
+            write!(out, "    apcb_size:   {:#x}", { h.apcb_size })?;
+            if h.apcb_size as usize > len {
+                write!(out, " (truncated to {len:#x})")?;
+            }
+            writeln!(out)?;
+            writeln!(out, "    instance:    {:#x}", {
+                h.unique_apcb_instance
+            })?;
+            writeln!(out, "    (use an APCB tool to decode its contents)")?;
+        }
+        DecodedSection::Coremap(map) => {
+            writeln!(out, "    Milan CCX core map")?;
+            writeln!(out, "    -------------------------------------")?;
+            writeln!(out, "    CCD   CCX   CORE  THREADS")?;
+            let mut suppressed = 0;
+            for ccd in &map.ccds {
+                let mut ccd_printed = false;
+                for ccx in &ccd.macd_ccxs {
+                    let mut ccx_printed = false;
+                    for core in &ccx.macx_cores {
+                        if !core.present() {
+                            suppressed += 1;
+                            continue;
+                        }
+                        if !ccd_printed {
+                            writeln!(out, "    {:#04x}", ccd.macd_id)?;
+                            ccd_printed = true;
+                        }
+                        if !ccx_printed {
+                            writeln!(out, "          {:#04x}", ccx.macx_id)?;
+                            ccx_printed = true;
+                        }
+                        let threads = core
+                            .threads()
+                            .map(|t| t.to_string())
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        writeln!(
+                            out,
+                            "                {:#04x}  {threads}",
+                            core.mac_id
+                        )?;
+                    }
+                }
+            }
+            if suppressed > 0 {
+                writeln!(
+                    out,
+                    "    ({suppressed} cores without threads hidden)"
+                )?;
+            }
+        }
+        DecodedSection::Smbios(structures) => {
+            writeln!(out, "    SMBIOS table fragment")?;
+            writeln!(out, "    -------------------------------------")?;
+            writeln!(out, "    HANDLE  TYPE  LENGTH  STRINGS")?;
+            for s in &structures {
+                writeln!(
+                    out,
+                    "    0x{:04x}  {:>4}  {:#6x}",
+                    { s.header.handle },
+                    s.header.ty,
+                    s.header.length
                 )?;
+                for (i, t) in s.strings().enumerate() {
+                    writeln!(
+                        out,
+                        "{:26}{}: \"{}\"",
+                        "",
+                        i + 1,
+                        t.escape_ascii()
+                    )?;
+                }
             }
         }
-        _ => (),
+        DecodedSection::Invalid(e) => {
+            writeln!(out, "    could not decode: {e}")?;
+        }
     }
     Ok(())
 }
 
-fn print_hex<W: Write>(out: &mut W, data: &[u8]) -> Result<(), std::io::Error> {
-    writeln!(
-        out,
-        "            00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f"
-    )?;
-    let mut addr = 0;
-    for d in data.chunks(16) {
-        write!(out, "    {addr:04x} |  ")?;
+/// Formats an entry as `GROUP/TYPE/INST @OFFSET size=SIZE`
+///
+/// As in the viewer, the group is marked with `*` if the entry is cancelled,
+/// or `+` if we can decode it.
+fn short_line(
+    offset: usize,
+    entry: &apob::ApobEntry,
+    data: &[u8],
+    decoders: &decode::Registry,
+) -> String {
+    let ty = entry.type_id();
+    let (group, ty) = match entry.group() {
+        Some(g) => (
+            apob::group_name(g).to_owned(),
+            apob::type_name(g, ty)
+                .map_or_else(|| format!("{ty:#x}"), str::to_owned),
+        ),
+        None => (format!("{:#x}", entry.raw_group()), format!("{ty:#x}")),
+    };
+    let decodable = !matches!(
+        decoders.decode(entry, data),
+        None | Some(DecodedSection::Invalid(..))
+    );
+    let mark = if entry.cancelled() {
+        "*"
+    } else if decodable {
+        "+"
+    } else {
+        ""
+    };
+    let mut out = format!(
+        "{group}{mark}/{ty}/{:#x} @{offset:#07x} size={:#x}",
+        { entry.inst },
+        data.len()
+    );
+    if entry.cancelled() {
+        out += " [cancelled]";
+    }
+    out
+}
+
+fn json_item(
+    offset: usize,
+    entry: &apob::ApobEntry,
+    data: &[u8],
+    decoders: &decode::Registry,
+) -> serde_json::Value {
+    use serde_json::json;
+    let decoded = match decoders.decode(entry, data) {
+        Some(DecodedSection::EventLog(events)) => Some(json!({
+            "count": events.len(),
+            "events": events,
+        })),
+        Some(DecodedSection::MemMap {
+            high_phys,
+            usable,
+            holes,
+        }) => Some(json!({
+            "high_phys": high_phys,
+            "usable": usable,
+            "holes": holes,
+        })),
+        Some(DecodedSection::PmuTrainFail(entries)) => Some(json!({
+            "nvalid": entries.len(),
+            "entries": entries,
+        })),
+        Some(DecodedSection::Coremap(map)) => Some(json!(map)),
+        Some(DecodedSection::Apcb { header, .. }) => Some(json!(header)),
+        Some(DecodedSection::Smbios(structures)) => Some(
+            structures
+                .iter()
+                .map(|s| {
+                    let handle = s.header.handle;
+                    json!({
+                        "type": s.header.ty,
+                        "length": s.header.length,
+                        "handle": handle,
+                        "strings": s.strings()
+                            .map(String::from_utf8_lossy)
+                            .collect::<Vec<_>>(),
+                    })
+                })
+                .collect(),
+        ),
+        Some(DecodedSection::PhyOverride(..) | DecodedSection::Invalid(..))
+        | None => None,
+    };
+    let mut out = json!({
+        "offset": offset,
+        "group": entry.group().map(apob::group_name),
+        "type": entry.type_id(),
+        "instance": ({ entry.inst }),
+        "data_size": data.len(),
+        "cancelled": entry.cancelled(),
+    });
+    if let Some(d) = decoded {
+        out["decoded"] = d;
+    } else {
+        out["raw"] = data
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>()
+            .into();
+    }
+    out
+}
+
+/// Prints a hex dump of `data`, labelling rows with offsets from `base`
+fn print_hex<W: Write>(
+    out: &mut W,
+    data: &[u8],
+    base: usize,
+    width: usize,
+) -> Result<(), std::io::Error> {
+    // Use at least 4 digits, widening to fit the largest offset
+    let last = base + data.len().saturating_sub(1);
+    let digits = (format!("{last:x}").len()).max(4);
+    write!(out, "{:1$}", "", digits + 7)?;
+    for i in 0..width {
+        write!(out, " {i:02x}")?;
+    }
+    writeln!(out)?;
+    let mut addr = base;
+    for d in data.chunks(width) {
+        write!(out, "    {addr:0digits$x} |  ")?;
         for c in d {
             write!(out, "{c:02x} ")?;
         }
-        for _ in 0..16 - d.len() {
+        for _ in 0..width - d.len() {
             write!(out, "   ")?;
         }
         write!(out, "| ")?;
@@ -0,0 +1,213 @@
+//! Field specs for viewing payloads of unknown types as structs
+//!
+//! A spec is a comma-separated list of fields, each of which is a scalar
+//! (`u8`, `u16`, `u32`, `u64`, `i8`, `i16`, `i32`, `i64`, `f32`, or `f64`) or
+//! an array of scalars (`[u8; 16]`).
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Scalar {
+    U8,
+    U16,
+    U32,
+    U64,
+    I8,
+    I16,
+    I32,
+    I64,
+    F32,
+    F64,
+}
+
+impl Scalar {
+    const ALL: [Scalar; 10] = [
+        Scalar::U8,
+        Scalar::U16,
+        Scalar::U32,
+        Scalar::U64,
+        Scalar::I8,
+        Scalar::I16,
+        Scalar::I32,
+        Scalar::I64,
+        Scalar::F32,
+        Scalar::F64,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Scalar::U8 => "u8",
+            Scalar::U16 => "u16",
+            Scalar::U32 => "u32",
+            Scalar::U64 => "u64",
+            Scalar::I8 => "i8",
+            Scalar::I16 => "i16",
+            Scalar::I32 => "i32",
+            Scalar::I64 => "i64",
+            Scalar::F32 => "f32",
+            Scalar::F64 => "f64",
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Scalar::U8 | Scalar::I8 => 1,
+            Scalar::U16 | Scalar::I16 => 2,
+            Scalar::U32 | Scalar::I32 | Scalar::F32 => 4,
+            Scalar::U64 | Scalar::I64 | Scalar::F64 => 8,
+        }
+    }
+
+    /// Formats a value, whose bytes must be exactly [`Scalar::size`] long
+    fn format(self, bytes: &[u8], big_endian: bool) -> String {
+        let mut buf = [0u8; 8];
+        let buf = &mut buf[..bytes.len()];
+        buf.copy_from_slice(bytes);
+        if big_endian {
+            buf.reverse();
+        }
+        // `buf` is now little-endian, so we can zero-extend it
+        let mut wide = [0u8; 8];
+        wide[..buf.len()].copy_from_slice(buf);
+        let v = u64::from_le_bytes(wide);
+        match self {
+            Scalar::U8 | Scalar::U16 | Scalar::U32 | Scalar::U64 => {
+                format!("{v:#x}")
+            }
+            Scalar::I8 => (v as u8 as i8).to_string(),
+            Scalar::I16 => (v as u16 as i16).to_string(),
+            Scalar::I32 => (v as u32 as i32).to_string(),
+            Scalar::I64 => (v as i64).to_string(),
+            Scalar::F32 => f32::from_bits(v as u32).to_string(),
+            Scalar::F64 => f64::from_bits(v).to_string(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Field {
+    ty: Scalar,
+    /// Number of elements, if this is an array
+    count: Option<usize>,
+}
+
+impl Field {
+    fn parse(s: &str) -> Result<Self, String> {
+        let scalar = |s: &str| {
+            let s = s.trim();
+            Scalar::ALL
+                .into_iter()
+                .find(|t| t.name() == s)
+                .ok_or_else(|| format!("unknown type `{s}`"))
+        };
+        let Some(inner) = s.strip_prefix('[') else {
+            return Ok(Field {
+                ty: scalar(s)?,
+                count: None,
+            });
+        };
+        let inner = inner
+            .strip_suffix(']')
+            .ok_or_else(|| format!("missing `]` in `{s}`"))?;
+        let (ty, count) = inner
+            .split_once(';')
+            .ok_or_else(|| format!("missing `;` in `{s}`"))?;
+        let count = crate::parse_int(count.trim())
+            .map_err(|_| format!("invalid length in `{s}`"))?;
+        Ok(Field {
+            ty: scalar(ty)?,
+            count: Some(count as usize),
+        })
+    }
+
+    fn name(&self) -> String {
+        match self.count {
+            Some(n) => format!("[{}; {n}]", self.ty.name()),
+            None => self.ty.name().to_owned(),
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.ty.size().saturating_mul(self.count.unwrap_or(1))
+    }
+}
+
+/// A field laid out over a payload
+pub struct FieldValue {
+    pub offset: usize,
+    pub ty: String,
+    pub value: String,
+}
+
+/// A parsed field spec, such as `u32,u32,u64,[u8;16]`
+#[derive(Clone, Debug)]
+pub struct Spec {
+    text: String,
+    fields: Vec<Field>,
+}
+
+impl Spec {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let fields = text
+            .split(',')
+            .map(|f| Field::parse(f.trim()))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Spec {
+            text: text.to_owned(),
+            fields,
+        })
+    }
+
+    /// Returns the spec as it was entered
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Lays the fields out in order from the start of `data`
+    ///
+    /// A field which runs past the end of `data` is shown as truncated and
+    /// ends the layout; bytes left over after the last field are shown as a
+    /// single pseudo-field.
+    pub fn layout(&self, data: &[u8], big_endian: bool) -> Vec<FieldValue> {
+        let mut out = vec![];
+        let mut offset = 0usize;
+        for f in &self.fields {
+            let Some(bytes) = data.get(offset..offset.saturating_add(f.size()))
+            else {
+                out.push(FieldValue {
+                    offset,
+                    ty: f.name(),
+                    value: format!("(truncated at {:#x})", data.len()),
+                });
+                return out;
+            };
+            let mut values = bytes
+                .chunks(f.ty.size())
+                .map(|b| f.ty.format(b, big_endian));
+            let value = match f.count {
+                None => values.next().unwrap_or_default(),
+                // Byte arrays are more readable as a run of hex digits
+                Some(_) if f.ty == Scalar::U8 => bytes
+                    .iter()
+                    .map(|b| format!("{b:02x}"))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                Some(_) => {
+                    format!("[{}]", values.collect::<Vec<_>>().join(", "))
+                }
+            };
+            out.push(FieldValue {
+                offset,
+                ty: f.name(),
+                value,
+            });
+            offset += f.size();
+        }
+        if offset < data.len() {
+            out.push(FieldValue {
+                offset,
+                ty: "..".to_owned(),
+                value: format!("{:#x} more bytes", data.len() - offset),
+            });
+        }
+        out
+    }
+}
@@ -0,0 +1,104 @@
+//! Machine-readable description of the APOB format, for other tooling
+//!
+//! Everything here comes from the `apob` crate's own definitions: names and
+//! values from its enums, and offsets and sizes from the compiler.
+
+use serde_json::{json, Value};
+use std::mem::{offset_of, size_of, size_of_val};
+
+/// Describes a struct and its public fields
+///
+/// Each field's size is taken from a copy of it, so that it can't drift from
+/// the field's type.
+macro_rules! layout {
+    ($ty:ident { $($field:ident),* $(,)? }) => {{
+        let zeroed = <apob::$ty as zerocopy::FromZeros>::new_zeroed();
+        json!({
+            "name": stringify!($ty),
+            "size": size_of::<apob::$ty>(),
+            "fields": [$(json!({
+                "name": stringify!($field),
+                "offset": offset_of!(apob::$ty, $field),
+                "size": size_of_val(&{ zeroed.$field }),
+            })),*],
+        })
+    }};
+}
+
+/// Builds the schema document
+pub fn schema() -> Value {
+    let groups = apob::ApobGroup::iter()
+        .map(|group| {
+            let types = (0..=u32::from(u8::MAX))
+                .filter_map(|ty| {
+                    let name = apob::type_name(group, ty)?;
+                    Some(json!({ "name": name, "value": ty }))
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "name": apob::group_name(group),
+                "value": group as u32,
+                "types": types,
+            })
+        })
+        .collect::<Vec<_>>();
+    let structs = [
+        layout!(ApobHeader {
+            sig,
+            version,
+            size,
+            offset
+        }),
+        layout!(ApobEntry {
+            group,
+            ty,
+            inst,
+            size,
+            hmac
+        }),
+        layout!(ApobEvent {
+            class,
+            info,
+            data0,
+            data1
+        }),
+        layout!(MilanApobEventLog { count, events }),
+        layout!(MilanApobCoremap { ccds }),
+        layout!(MilanApobCcd { macd_id, macd_ccxs }),
+        layout!(MilanApobCcx {
+            macx_id,
+            macx_cores
+        }),
+        layout!(MilanApobCore {
+            mac_id,
+            mac_thread_exists
+        }),
+        layout!(ApobSysMemMap {
+            high_phys,
+            hole_count
+        }),
+        layout!(ApobSysMemMapHole { base, size, ty }),
+        layout!(MilanApobPhyOverride {
+            map_datalen,
+            map_data
+        }),
+        layout!(PmuTfiEntry { bits, error, data }),
+        layout!(PmuTfi { nvalid, entries }),
+        layout!(SmbiosHeader { ty, length, handle }),
+        layout!(ApcbHeader {
+            sig,
+            header_size,
+            version,
+            apcb_size,
+            unique_apcb_instance,
+            checksum_byte,
+        }),
+    ];
+    json!({
+        "signature": String::from_utf8_lossy(&apob::APOB_SIG),
+        "version": apob::APOB_VERSION,
+        "cancelled_mask": apob::APOB_CANCELLED,
+        "groups": groups,
+        "structs": structs,
+    })
+}
@@ -0,0 +1,73 @@
+//! Persistent per-file viewer state
+//!
+//! The interactive viewer saves its selection and display settings when it
+//! exits, keyed by a hash of the input file's path and contents, then restores
+//! them the next time the same file is opened.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ViewState {
+    /// Number of items when the state was saved, used to detect mismatches
+    pub item_count: usize,
+    /// Index of the selected item
+    pub selected: usize,
+    /// Data pane scroll position (in rows) for each item index
+    pub data_scroll: HashMap<usize, usize>,
+    /// Bytes per data pane row when `data_scroll` was recorded
+    pub data_width: usize,
+    /// Bytes per data group
+    pub grouping: usize,
+    pub big_endian: bool,
+    pub colors: bool,
+    /// Whether runs of all-zero rows are squeezed, which affects the meaning
+    /// of `data_scroll`
+    #[serde(default)]
+    pub squeeze: bool,
+    /// Width of the entry table, or 0 for the default
+    #[serde(default)]
+    pub table_width: u16,
+    /// Indices of bookmarked items
+    #[serde(default)]
+    pub bookmarks: BTreeSet<usize>,
+}
+
+/// 64-bit FNV-1a, which is stable across builds (unlike `DefaultHasher`)
+fn fnv1a(hash: u64, data: &[u8]) -> u64 {
+    data.iter().fold(hash, |h, b| {
+        (h ^ u64::from(*b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Returns the state file for the given input, if a state directory exists
+pub fn path(name: &Path, data: &[u8]) -> Option<PathBuf> {
+    let dir = match std::env::var_os("XDG_STATE_HOME") {
+        Some(d) => PathBuf::from(d),
+        None => PathBuf::from(std::env::var_os("HOME")?).join(".local/state"),
+    };
+    let name = std::fs::canonicalize(name).unwrap_or_else(|_| name.into());
+    let hash =
+        fnv1a(0xcbf2_9ce4_8422_2325, name.as_os_str().as_encoded_bytes());
+    let hash = fnv1a(hash, data);
+    Some(dir.join("apob-cli").join(format!("{hash:016x}.json")))
+}
+
+/// Loads saved state, returning `None` if it is missing or unreadable
+pub fn load(path: &Path) -> Option<ViewState> {
+    let data = std::fs::read(path).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+/// Saves state, ignoring errors (since this is merely a convenience)
+pub fn save(path: &Path, state: &ViewState) {
+    if let Some(dir) = path.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    if let Ok(data) = serde_json::to_vec(state) {
+        let _ = std::fs::write(path, data);
+    }
+}
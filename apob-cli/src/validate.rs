@@ -0,0 +1,105 @@
+//! Structural checks for unattended validation of APOB files
+
+use crate::decode::{DecodedSection, Registry};
+use apob::ApobError;
+use serde::Serialize;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Suspicious, but the blob is still usable
+    Warning,
+    /// The blob is malformed
+    Error,
+}
+
+/// A single problem found by [`validate`]
+#[derive(Debug, Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    /// Name of the check which produced this finding
+    pub check: &'static str,
+    /// File offset of the offending item, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+    pub message: String,
+}
+
+/// Runs every structural check against a blob
+///
+/// This checks the header, the padding, the layout of entries, and the
+/// payload of every entry which we know how to decode.
+pub fn validate(data: &[u8], decoders: &Registry) -> Vec<Finding> {
+    let finding = |severity, check, offset, message| Finding {
+        severity,
+        check,
+        offset,
+        message,
+    };
+    let apob = match apob::Apob::parse(data) {
+        Ok(apob) => apob,
+        Err(e) => {
+            return vec![finding(
+                Severity::Error,
+                "header",
+                Some(0),
+                e.to_string(),
+            )]
+        }
+    };
+
+    let mut out = vec![];
+    if !apob.padding_is_zero() {
+        out.push(finding(
+            Severity::Warning,
+            "padding",
+            Some(std::mem::size_of::<apob::ApobHeader>()),
+            "padding contains non-zero bytes".to_owned(),
+        ));
+    }
+    if let Err(errs) = apob.validate_layout() {
+        for e in errs {
+            // Dumps are often padded out to the size of a flash region, so
+            // data after the last entry isn't necessarily a problem
+            let (severity, offset) = match e {
+                ApobError::TrailingData { offset, .. } => {
+                    (Severity::Warning, Some(offset))
+                }
+                ApobError::EntryPastEnd { offset, .. } => {
+                    (Severity::Error, Some(offset))
+                }
+                _ => (Severity::Error, None),
+            };
+            out.push(finding(severity, "layout", offset, e.to_string()));
+        }
+    }
+
+    let mut pos = apob.entries_offset();
+    for (entry, payload) in apob.entries().map_while(Result::ok) {
+        if let Some(DecodedSection::Invalid(e)) =
+            decoders.decode(entry, payload)
+        {
+            // Cancelled entries have been superseded, so firmware won't
+            // consume their payloads
+            let severity = if entry.cancelled() {
+                Severity::Warning
+            } else {
+                Severity::Error
+            };
+            let group = match entry.group() {
+                Some(g) => apob::group_name(g).to_owned(),
+                None => format!("{:#x}", entry.raw_group()),
+            };
+            out.push(finding(
+                severity,
+                "payload",
+                Some(pos),
+                format!("{group}/{:#x}/{:#x}: {e}", entry.type_id(), {
+                    entry.inst
+                }),
+            ));
+        }
+        pos += entry.size as usize;
+    }
+    out
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use apob::{ApobFabricType, ApobGroup};
+use common::run;
+
+/// Writes a blob with several entries of type 9 in different groups
+fn write_blob(name: &str) -> std::path::PathBuf {
+    let data = common::blob(&[
+        (ApobGroup::GENERAL, 9, 0, &[0; 8]),
+        (
+            ApobGroup::FABRIC,
+            ApobFabricType::SYS_MEM_MAP as u32,
+            0,
+            &common::mem_map(0x10_8000_0000, &[]),
+        ),
+        (
+            ApobGroup::FABRIC,
+            ApobFabricType::MILAN_FABRIC_PHY_OVERRIDE as u32,
+            0,
+            &[0; 260],
+        ),
+        (ApobGroup::MEMORY, 9, 0, &[0; 8]),
+    ]);
+    common::write(name, &data)
+}
+
+/// Runs the CLI, returning the listed entries as `(group, type)`
+fn listed(args: &[&str]) -> (Vec<(String, String)>, String) {
+    let out = run(args);
+    assert!(out.status.success(), "{out:?}");
+    let text = String::from_utf8(out.stdout).unwrap();
+    let rows = text
+        .lines()
+        .filter(|line| line.starts_with("0x"))
+        .map(|line| {
+            let cols = line.split_whitespace().collect::<Vec<_>>();
+            (cols[1].to_owned(), cols[2].to_owned())
+        })
+        .collect();
+    (rows, text)
+}
+
+#[test]
+fn group_and_type_isolate_mem_map() {
+    let path = write_blob("group_and_type.bin");
+    let path = path.to_str().unwrap();
+
+    let (rows, text) =
+        listed(&["--group", "FABRIC", "--type", "9", "--decode", path]);
+    assert_eq!(rows, [("FABRIC".to_owned(), "9".to_owned())]);
+    assert!(text.contains("high_phys: 0x1080000000"), "{text}");
+
+    // Without a group, the type matches in every group
+    let (rows, _) = listed(&["--type", "0x9", path]);
+    let groups = rows.iter().map(|(g, _)| g.as_str()).collect::<Vec<_>>();
+    assert_eq!(groups, ["GENERAL", "FABRIC", "MEMORY"]);
+}
+
+#[test]
+fn count_applies_filters() {
+    let path = write_blob("count.bin");
+    let path = path.to_str().unwrap();
+    let count = |args: &[&str]| {
+        let out = run(&[args, &["--count", path]].concat());
+        assert!(out.status.success(), "{out:?}");
+        String::from_utf8(out.stdout).unwrap()
+    };
+    assert_eq!(count(&[]), "4\n");
+    assert_eq!(count(&["--group", "FABRIC"]), "2\n");
+    assert_eq!(count(&["--type", "9"]), "3\n");
+}
+
+#[test]
+fn raw_only_unknown_skips_decoded() {
+    let path = write_blob("raw_only_unknown.bin");
+    let path = path.to_str().unwrap();
+
+    // Only the FABRIC type 9 has a decoder
+    let (rows, text) = listed(&["--raw-only-unknown", path]);
+    let groups = rows.iter().map(|(g, _)| g.as_str()).collect::<Vec<_>>();
+    assert_eq!(groups, ["GENERAL", "MEMORY"]);
+    assert!(text.contains("0000 |  00 00 00 00 00 00 00 00"), "{text}");
+
+    let (rows, _) = listed(&["--raw-only-unknown", "--group", "FABRIC", path]);
+    assert!(rows.is_empty());
+}
+
+#[test]
+fn decode_only_selects_group_and_type() {
+    let path = write_blob("decode_only.bin");
+    let path = path.to_str().unwrap();
+
+    let (rows, text) = listed(&["--decode-only", "FABRIC/SYS_MEM_MAP", path]);
+    assert_eq!(rows, [("FABRIC".to_owned(), "9".to_owned())]);
+    assert!(text.contains("high_phys: 0x1080000000"), "{text}");
+
+    let (rows, _) = listed(&[
+        "--decode-only",
+        "GENERAL/9",
+        "--decode-only",
+        "memory/0x9",
+        path,
+    ]);
+    let groups = rows.iter().map(|(g, _)| g.as_str()).collect::<Vec<_>>();
+    assert_eq!(groups, ["GENERAL", "MEMORY"]);
+}
+
+#[test]
+fn no_header_hides_blob_header() {
+    let path = write_blob("no_header.bin");
+    let path = path.to_str().unwrap();
+
+    let (_, text) = listed(&[path]);
+    assert!(text.starts_with("ApobHeader {"), "{text}");
+    let (rows, text) = listed(&["--no-header", "--no-padding", path]);
+    assert!(text.starts_with("OFFSET"), "{text}");
+    assert_eq!(rows.len(), 4);
+}
+
+#[test]
+fn entry_selects_by_index() {
+    let path = write_blob("entry.bin");
+    let path = path.to_str().unwrap();
+
+    let (rows, text) = listed(&["--entry", "1", "--decode", path]);
+    assert_eq!(rows, [("FABRIC".to_owned(), "9".to_owned())]);
+    assert!(text.contains("high_phys: 0x1080000000"), "{text}");
+
+    let out = run(&["--entry", "4", path]);
+    assert!(!out.status.success());
+    let err = String::from_utf8(out.stderr).unwrap();
+    assert!(err.contains("no entry 4; the blob has 4 entries"), "{err}");
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use apob::ApobGroup;
+use common::run;
+
+/// Writes a blob with one entry into the middle of a larger image, returning
+/// the image's path and the blob's offset
+fn write_image(name: &str) -> (std::path::PathBuf, usize) {
+    let blob = common::blob(&[(ApobGroup::GENERAL, 9, 0, &[0; 8])]);
+    let image = [vec![0xff; 0x1000], blob, vec![0xff; 0x100]].concat();
+    (common::write(name, &image), 0x1000)
+}
+
+#[test]
+fn offset_reads_embedded_blob() {
+    let (path, offset) = write_image("embedded.bin");
+    let path = path.to_str().unwrap();
+
+    let out = run(&["--offset", &format!("{offset:#x}"), "--count", path]);
+    assert!(out.status.success(), "{out:?}");
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "1\n");
+
+    // Anything after the blob's declared size is left out
+    let out = run(&["--offset", &offset.to_string(), "--strict", path]);
+    assert!(out.status.success(), "{out:?}");
+}
+
+#[test]
+fn offset_without_signature() {
+    let (path, offset) = write_image("no_signature.bin");
+    let path = path.to_str().unwrap();
+
+    let out = run(&["--offset", &(offset + 4).to_string(), path]);
+    assert!(!out.status.success());
+    let err = String::from_utf8(out.stderr).unwrap();
+    assert!(err.contains("no APOB signature there"), "{err}");
+
+    let out = run(&["--offset", "0x100000", path]);
+    assert!(!out.status.success());
+    let err = String::from_utf8(out.stderr).unwrap();
+    assert!(err.contains("past the end of the file"), "{err}");
+}
+
+#[test]
+fn scan_finds_blob() {
+    let (path, offset) = write_image("scan.bin");
+    let path = path.to_str().unwrap();
+
+    let out = run(&["--scan", "--count", path]);
+    assert!(out.status.success(), "{out:?}");
+    assert_eq!(String::from_utf8(out.stdout).unwrap(), "1\n");
+    let err = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        err.contains(&format!("found a blob at {offset:#x}")),
+        "{err}"
+    );
+
+    let out = run(&["--scan-all", path]);
+    assert!(out.status.success(), "{out:?}");
+    let text = String::from_utf8(out.stdout).unwrap();
+    assert!(
+        text.starts_with(&format!("{offset:#x}  version 0x18")),
+        "{text}"
+    );
+    assert_eq!(text.lines().count(), 1);
+}
+
+#[test]
+fn image_selects_unaligned_blob() {
+    let blob = |inst| common::blob(&[(ApobGroup::GENERAL, 9, inst, &[0; 8])]);
+    // The second image starts at an odd offset
+    let image = [blob(0), vec![0xff; 3], blob(1)].concat();
+    let path = common::write("unaligned_images.bin", &image);
+    let path = path.to_str().unwrap();
+
+    let out = run(&[path]);
+    assert!(out.status.success(), "{out:?}");
+    let text = String::from_utf8(out.stdout).unwrap();
+    assert!(text.contains("file contains 2 images"), "{text}");
+
+    let out = run(&["--image", "1", "--short", path]);
+    assert!(out.status.success(), "{out:?}");
+    let text = String::from_utf8(out.stdout).unwrap();
+    assert!(text.contains("/0x1 @"), "{text}");
+}
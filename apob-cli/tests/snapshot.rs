@@ -0,0 +1,185 @@
+//! Compares the CLI's text output against golden files
+//!
+//! Run with `UPDATE_SNAPSHOTS=1` to rewrite the files in `tests/snapshots`
+//! after an intentional change to the output, then review the diff.
+mod common;
+
+use apob::{
+    ApobCcxType, ApobEntry, ApobEvent, ApobFabricType, ApobGeneralType,
+    ApobGroup, ApobMemoryType, MilanApobCoremap, MilanApobEventClass,
+    MilanApobPhyOverride, PmuTfiEntry, PmuTfiEntryBitfield,
+};
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+use zerocopy::{FromZeros, IntoBytes};
+
+fn event_log() -> Vec<u8> {
+    common::event_log(&[
+        ApobEvent {
+            class: MilanApobEventClass::ALERT as u32,
+            info: 0x1234,
+            data0: 1,
+            data1: 2,
+        },
+        // Training error, whose data is decoded further
+        ApobEvent {
+            class: MilanApobEventClass::FATAL as u32,
+            info: 0x4001,
+            data0: 0x0001_0211,
+            data1: 0x3,
+        },
+    ])
+}
+
+fn mem_map() -> Vec<u8> {
+    common::mem_map(
+        0x10_8000_0000,
+        &[(0xa_0000, 0x6_0000, 1), (0x8000_0000, 0x8000_0000, 2)],
+    )
+}
+
+fn pmu_tfi() -> Vec<u8> {
+    common::pmu_tfi(&[PmuTfiEntry {
+        bits: PmuTfiEntryBitfield(0x0001_8023),
+        error: 0x11,
+        data: [1, 2, 3, 4],
+    }])
+}
+
+fn phy_override() -> Vec<u8> {
+    let mut phy = MilanApobPhyOverride::new_zeroed();
+    phy.map_datalen = 6;
+    phy.map_data[..6].copy_from_slice(&[1, 2, 3, 4, 5, 6]);
+    phy.as_bytes().to_vec()
+}
+
+fn coremap() -> Vec<u8> {
+    let mut map = MilanApobCoremap::new_zeroed();
+    let ccx = &mut map.ccds[0].macd_ccxs[0];
+    for (i, core) in ccx.macx_cores[..2].iter_mut().enumerate() {
+        core.mac_id = i as u8;
+        core.mac_thread_exists = [1, 1];
+    }
+    map.as_bytes().to_vec()
+}
+
+fn apcb() -> Vec<u8> {
+    [
+        b"APCB".as_slice(),
+        &0x20u16.to_le_bytes(),   // header_size
+        &0x30u16.to_le_bytes(),   // version
+        &0x1000u32.to_le_bytes(), // apcb_size, more than is embedded
+        &3u32.to_le_bytes(),      // unique_apcb_instance
+        &[0; 16],
+        &[0xcc; 16],
+    ]
+    .concat()
+}
+
+fn smbios() -> Vec<u8> {
+    [
+        // Type 1, referring to two strings
+        [1, 6, 0, 1, 1, 2].as_slice(),
+        b"Oxide\0Gimlet\0\0",
+        // End of table
+        &[127, 4, 1, 1, 0, 0],
+    ]
+    .concat()
+}
+
+/// Writes a blob with one entry for each decoder, plus an entry of unknown
+/// type and one in an unknown group
+fn write_blob(name: &str) -> PathBuf {
+    let mut data = common::blob(&[
+        (
+            ApobGroup::GENERAL,
+            ApobGeneralType::EVENT_LOG as u32,
+            0,
+            &event_log(),
+        ),
+        (
+            ApobGroup::FABRIC,
+            ApobFabricType::SYS_MEM_MAP as u32,
+            0,
+            &mem_map(),
+        ),
+        (
+            ApobGroup::FABRIC,
+            ApobFabricType::MILAN_FABRIC_PHY_OVERRIDE as u32,
+            0,
+            &phy_override(),
+        ),
+        (
+            ApobGroup::MEMORY,
+            ApobMemoryType::MILAN_PMU_TRAIN_FAIL as u32,
+            0,
+            &pmu_tfi(),
+        ),
+        (
+            ApobGroup::CCX,
+            ApobCcxType::MILAN_COREMAP as u32,
+            0,
+            &coremap(),
+        ),
+        (ApobGroup::APCB, 1, 0, &apcb()),
+        (ApobGroup::SMBIOS, 1, 0, &smbios()),
+        (ApobGroup::MEMORY, 0x77, 0, &[0x12, 0x34, 0x56, 0x78]),
+        (ApobGroup::GENERAL, 1, 0, &[0x9a, 0xbc, 0xde, 0xf0]),
+    ]);
+    // The builder only takes known groups, so move the last entry by hand
+    let last = data.len() - size_of::<ApobEntry>() - 4;
+    data[last..last + 4].copy_from_slice(&0x77u32.to_le_bytes());
+    common::write(name, &data)
+}
+
+/// Runs the CLI on the fixture and compares its output with `name`
+fn check(name: &str, args: &[&str]) {
+    let blob = write_blob(&name.replace(".txt", ".bin"));
+    let out = common::run(
+        &[&["--color", "never"], args, &[blob.to_str().unwrap()]].concat(),
+    );
+    assert!(out.status.success(), "{out:?}");
+    let text = String::from_utf8(out.stdout).unwrap();
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(name);
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::write(&path, &text).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("could not read {}: {e}", path.display()));
+    if text != expected {
+        panic!(
+            "output does not match {}; rerun with UPDATE_SNAPSHOTS=1 to \
+             update it\n--- expected\n{expected}\n--- actual\n{text}",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn table() {
+    check("table.txt", &[]);
+}
+
+#[test]
+fn decode() {
+    check("decode.txt", &["--decode"]);
+}
+
+#[test]
+fn raw() {
+    check("raw.txt", &["--raw"]);
+}
+
+#[test]
+fn short() {
+    check("short.txt", &["--short"]);
+}
+
+#[test]
+fn strings() {
+    check("strings.txt", &["--strings"]);
+}
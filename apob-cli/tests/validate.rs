@@ -0,0 +1,34 @@
+mod common;
+
+use apob::{ApobFabricType, ApobGroup, APOB_CANCELLED};
+use common::run;
+
+#[test]
+fn cancelled_entry_with_bad_payload_warns() {
+    // A memory map which is too short to hold its header
+    let mut data = common::blob(&[(
+        ApobGroup::FABRIC,
+        ApobFabricType::SYS_MEM_MAP as u32,
+        0,
+        &[0; 4],
+    )]);
+    // Cancel the entry, setting the same bits in its type
+    let entry = std::mem::size_of::<apob::ApobHeader>();
+    for field in [entry, entry + 4] {
+        let v = u32::from_le_bytes(data[field..][..4].try_into().unwrap());
+        data[field..][..4].copy_from_slice(&(v | APOB_CANCELLED).to_le_bytes());
+    }
+    let path = common::write("cancelled_bad_payload.bin", &data);
+    let path = path.to_str().unwrap();
+
+    let out = run(&["validate", path]);
+    assert!(out.status.success(), "{out:?}");
+    let report: serde_json::Value =
+        serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(report["errors"], 0, "{report}");
+    assert_eq!(report["warnings"], 1, "{report}");
+    assert_eq!(report["findings"][0]["check"], "payload", "{report}");
+
+    let out = run(&["validate", "--strict", path]);
+    assert!(!out.status.success(), "{out:?}");
+}
@@ -0,0 +1,83 @@
+use crate::{ApobEntry, ApobGroup, ApobHeader, APOB_SIG};
+
+use alloc::vec::Vec;
+use zerocopy::IntoBytes;
+
+/// Builder to synthesize an APOB blob
+///
+/// Entries are laid out end-to-end, starting at the header's `offset`; the
+/// region between the header and the first entry is zero-filled.  Each payload
+/// is zero-padded to a multiple of 4 bytes, and the padding is counted in the
+/// entry's `size`, so every entry header stays aligned.  HMACs are left zeroed.
+#[derive(Clone, Debug)]
+pub struct ApobBuilder {
+    version: u32,
+    offset: u32,
+    entries: Vec<u8>,
+}
+
+impl ApobBuilder {
+    pub fn new(version: u32) -> Self {
+        Self {
+            version,
+            offset: core::mem::size_of::<ApobHeader>() as u32,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Sets the offset of the first entry, padding after the header
+    ///
+    /// # Panics
+    /// If `offset` is smaller than the header
+    pub fn offset(&mut self, offset: u32) -> &mut Self {
+        assert!(offset as usize >= core::mem::size_of::<ApobHeader>());
+        self.offset = offset;
+        self
+    }
+
+    /// Appends an entry with the given payload
+    ///
+    /// # Panics
+    /// If the padded entry is larger than `u32::MAX` bytes
+    pub fn push_entry(
+        &mut self,
+        group: ApobGroup,
+        ty: u32,
+        inst: u32,
+        data: &[u8],
+    ) -> &mut Self {
+        let size = (core::mem::size_of::<ApobEntry>() + data.len())
+            .next_multiple_of(4);
+        let entry = ApobEntry {
+            group: group as u32,
+            ty,
+            inst,
+            size: u32::try_from(size).unwrap(),
+            hmac: [0; 32],
+        };
+        self.entries.extend_from_slice(entry.as_bytes());
+        self.entries.extend_from_slice(data);
+        self.entries
+            .resize(self.entries.len().next_multiple_of(4), 0);
+        self
+    }
+
+    /// Serializes the header, padding, and entries
+    ///
+    /// # Panics
+    /// If the blob is larger than `u32::MAX` bytes
+    pub fn finish(&self) -> Vec<u8> {
+        let size = self.offset as usize + self.entries.len();
+        let header = ApobHeader {
+            sig: APOB_SIG,
+            version: self.version,
+            size: u32::try_from(size).unwrap(),
+            offset: self.offset,
+        };
+        let mut out = Vec::with_capacity(size);
+        out.extend_from_slice(header.as_bytes());
+        out.resize(self.offset as usize, 0);
+        out.extend_from_slice(&self.entries);
+        out
+    }
+}
@@ -0,0 +1,27 @@
+use crate::{ApobEntry, APOB_HMAC_LEN};
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use zerocopy::IntoBytes;
+
+impl ApobEntry {
+    /// Checks the entry's stored HMAC against its contents
+    ///
+    /// The HMAC-SHA256 is computed over the 48-byte [`ApobEntry`] header (with
+    /// the `hmac` field set to all zeros), followed by the payload `data`.  In
+    /// other words, it covers [`ApobEntry::hmac_covered_range`] of the blob,
+    /// with bytes `16..48` of that range zeroed.  This layout is inferred,
+    /// not taken from firmware documentation.
+    ///
+    /// The comparison is constant-time.
+    pub fn verify_hmac(&self, data: &[u8], key: &[u8]) -> bool {
+        let mut header = *self;
+        header.hmac = [0; APOB_HMAC_LEN];
+        let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(key) else {
+            return false;
+        };
+        mac.update(header.as_bytes());
+        mac.update(data);
+        mac.verify_slice(&self.hmac).is_ok()
+    }
+}
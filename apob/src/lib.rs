@@ -1,16 +1,57 @@
 #![no_std]
 
-use strum_macros::FromRepr;
-use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+mod builder;
+#[cfg(feature = "alloc")]
+pub use builder::ApobBuilder;
+
+#[cfg(feature = "alloc")]
+mod owned;
+#[cfg(feature = "alloc")]
+pub use owned::ApobOwned;
+
+#[cfg(feature = "hmac")]
+mod hmac;
+
+#[cfg(feature = "serde")]
+mod ser;
+
+/// Emits a `tracing` event at the given level, such as `debug`
+///
+/// Without the `tracing` feature this expands to nothing, so arguments must
+/// not have side effects.
+#[cfg(feature = "tracing")]
+macro_rules! event {
+    ($level:ident, $($arg:tt)*) => {
+        tracing::$level!($($arg)*)
+    };
+}
+#[cfg(not(feature = "tracing"))]
+macro_rules! event {
+    ($level:ident, $($arg:tt)*) => {};
+}
+
+use core::ops::Range;
+use strum_macros::{FromRepr, IntoStaticStr};
+use zerocopy::{ConvertError, FromBytes, Immutable, IntoBytes, KnownLayout};
 
 /// Signature, which must be the first 4 bytes of the blob
 pub const APOB_SIG: [u8; 4] = *b"APOB";
 
+/// [`APOB_SIG`] as it reads when each 32-bit word has been byte-swapped
+///
+/// Some transports deliver blobs in this form; see [`ApobError::ByteSwapped`].
+pub const APOB_SIG_SWAPPED: [u8; 4] = *b"BOPA";
+
 /// Known version
 pub const APOB_VERSION: u32 = 0x18;
 
 #[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
-#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C, packed)]
 pub struct ApobHeader {
     pub sig: [u8; 4],
     pub version: u32,
@@ -18,9 +59,66 @@ pub struct ApobHeader {
     pub offset: u32,
 }
 
+// Structs read from the blob must match AGESA's layout exactly, so their
+// sizes are checked at compile time.  They're also packed, so that a blob can
+// be read from any offset within a caller's buffer.  The header is four
+// `u32`s, 16 bytes.
+const _: () = assert!(core::mem::size_of::<ApobHeader>() == 16);
+const _: () = assert!(core::mem::align_of::<ApobHeader>() == 1);
+
+impl TryFrom<&[u8]> for ApobHeader {
+    type Error = ApobError;
+
+    /// Copies the header from the start of `data`, without checking it
+    fn try_from(data: &[u8]) -> Result<Self, ApobError> {
+        read_prefix(data)
+    }
+}
+
+impl ApobHeader {
+    /// Checks the header's own fields against a buffer of `buf_len` bytes
+    ///
+    /// The signature and version must match, the entries must start after
+    /// the header and within the buffer, and the declared size must cover
+    /// the start of the entries.  A declared size past the end of the buffer
+    /// is allowed, since captures are often truncated;
+    /// `Apob::validate_layout` reports it instead.
+    pub fn validate(&self, buf_len: usize) -> Result<(), ApobError> {
+        self.check(buf_len, true)
+    }
+
+    fn check(
+        &self,
+        buf_len: usize,
+        check_version: bool,
+    ) -> Result<(), ApobError> {
+        if self.sig == APOB_SIG_SWAPPED {
+            return Err(ApobError::ByteSwapped);
+        }
+        if self.sig != APOB_SIG {
+            return Err(ApobError::BadSignature(self.sig));
+        }
+        if check_version && self.version != APOB_VERSION {
+            return Err(ApobError::BadVersion(self.version));
+        }
+        let offset = usize::try_from(self.offset).unwrap_or(usize::MAX);
+        if offset < core::mem::size_of::<ApobHeader>() || offset > buf_len {
+            return Err(ApobError::OffsetOutOfBounds {
+                offset,
+                len: buf_len,
+            });
+        }
+        let size = usize::try_from(self.size).unwrap_or(usize::MAX);
+        if size < offset {
+            return Err(ApobError::OffsetOutOfBounds { offset, len: size });
+        }
+        Ok(())
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 
-#[derive(Copy, Clone, Debug, FromRepr)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, FromRepr, IntoStaticStr)]
 #[allow(non_camel_case_types)]
 pub enum ApobGroup {
     MEMORY = 1,
@@ -35,12 +133,91 @@ pub enum ApobGroup {
     APCB,
 }
 
+impl ApobGroup {
+    /// Iterates over every known group, in order of value
+    pub fn iter() -> impl Iterator<Item = Self> {
+        (0..=u8::MAX as usize).filter_map(Self::from_repr)
+    }
+
+    /// Looks up a group by its name, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::iter().find(|g| <&str>::from(g).eq_ignore_ascii_case(name))
+    }
+}
+
+/// Returns the name of a group, for labeling it in output
+pub fn group_name(group: ApobGroup) -> &'static str {
+    group.into()
+}
+
+/// Returns the name of a known type within a group
+///
+/// The cancellation bits of `ty` are ignored.
+pub fn type_name(group: ApobGroup, ty: u32) -> Option<&'static str> {
+    let ty = (ty & !APOB_CANCELLED) as usize;
+    match group {
+        ApobGroup::GENERAL => ApobGeneralType::from_repr(ty).map(Into::into),
+        ApobGroup::CCX => ApobCcxType::from_repr(ty).map(Into::into),
+        ApobGroup::FABRIC => ApobFabricType::from_repr(ty).map(Into::into),
+        ApobGroup::MEMORY => ApobMemoryType::from_repr(ty).map(Into::into),
+        ApobGroup::DF
+        | ApobGroup::PSP
+        | ApobGroup::NBIO
+        | ApobGroup::FCH
+        | ApobGroup::SMBIOS
+        | ApobGroup::APCB => None,
+    }
+}
+
+/// Decodes a Milan event log payload, returning the valid events
+pub fn event_log(data: &[u8]) -> Result<&[ApobEvent], ApobError> {
+    let log = payload_as::<MilanApobEventLog>(data)?;
+    let count = usize::from(log.count);
+    log.events.get(..count).ok_or(ApobError::InvalidCount {
+        count,
+        max: log.events.len(),
+    })
+}
+
+/// Returns the name of a Milan event info code, if known
+pub fn event_info_name(info: u32) -> Option<&'static str> {
+    MilanApobEventInfo::from_repr(info as usize).map(|i| i.into())
+}
+
+/// Describes a Milan event's `data0` and `data1`, with one line per detail
+///
+/// Returns no lines if we don't know how the given info code uses the data.
+/// New codes should be added to `MILAN_EVENT_DATA`, so that every renderer
+/// picks them up.
+#[cfg(feature = "alloc")]
+pub fn decode_event_data(
+    info: u32,
+    data0: u32,
+    data1: u32,
+) -> alloc::vec::Vec<alloc::string::String> {
+    MILAN_EVENT_DATA
+        .iter()
+        .find(|(code, _)| *code as u32 == info)
+        .map_or_else(alloc::vec::Vec::new, |(_, f)| f(data0, data1))
+}
+
+/// Describes an event's `data0` and `data1`, as in [`decode_event_data`]
+#[cfg(feature = "alloc")]
+type EventDataDecoder = fn(u32, u32) -> alloc::vec::Vec<alloc::string::String>;
+
 /// Mask applied to [`ApobEntry::group`] to cancel the group
+///
+/// Firmware cancels an entry by setting all of the upper 16 bits of its
+/// `group` field, leaving the original group in the lower 16 bits; an entry is
+/// only cancelled if every one of these bits is set.  The same bits may also be
+/// set in `ty`, so both fields should be masked with `!APOB_CANCELLED` before
+/// being compared against known values.
 pub const APOB_CANCELLED: u32 = 0xFFFF_0000;
 const APOB_HMAC_LEN: usize = 32;
 
 #[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
-#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C, packed)]
 pub struct ApobEntry {
     pub group: u32,
     pub ty: u32,
@@ -52,11 +229,36 @@ pub struct ApobEntry {
     // data is trailing behind here
 }
 
+// Four `u32`s and a 32-byte HMAC, 48 bytes
+const _: () = assert!(core::mem::size_of::<ApobEntry>() == 48);
+const _: () = assert!(core::mem::align_of::<ApobEntry>() == 1);
+
 impl ApobEntry {
     /// Returns the group, or `None` if the type is unknown
+    ///
+    /// This is the same as [`ApobEntry::original_group`], so cancelled
+    /// entries still report the group they belonged to.
     pub fn group(&self) -> Option<ApobGroup> {
-        let group = self.group & !APOB_CANCELLED;
-        ApobGroup::from_repr(group as usize)
+        self.original_group()
+    }
+
+    /// Returns the group from the lower 16 bits of `group`
+    ///
+    /// Cancellation only touches the upper 16 bits (see [`APOB_CANCELLED`]),
+    /// so this is the group the entry had before it was cancelled, or `None`
+    /// if that group is unknown.
+    pub fn original_group(&self) -> Option<ApobGroup> {
+        ApobGroup::from_repr(self.raw_group() as usize)
+    }
+
+    /// Returns `group` without the cancellation bits, even if it's unknown
+    pub fn raw_group(&self) -> u32 {
+        self.group & !APOB_CANCELLED
+    }
+
+    /// Returns `ty` without the cancellation bits
+    pub fn type_id(&self) -> u32 {
+        self.ty & !APOB_CANCELLED
     }
     /// Checks whether this group has been cancelled
     ///
@@ -64,12 +266,549 @@ impl ApobEntry {
     pub fn cancelled(&self) -> bool {
         (self.group & APOB_CANCELLED) == APOB_CANCELLED
     }
+
+    /// Returns the bytes of the blob covered by the entry's HMAC, given the
+    /// offset of the entry within the blob
+    ///
+    /// This is the whole entry as it is laid out in the blob: the 48-byte
+    /// header followed by the payload, for `size` bytes in total, with the
+    /// `hmac` field (bytes `16..48` of the range) zeroed before hashing.
+    /// We have no documentation of what the firmware signs, so this range is
+    /// inferred rather than confirmed; callers with their own crypto can
+    /// slice it out of the blob to check the same bytes.
+    ///
+    /// The range isn't checked against any buffer; a `size` which runs past
+    /// the end of the address space is clamped.
+    #[cfg_attr(
+        feature = "hmac",
+        doc = "",
+        doc = "[`ApobEntry::verify_hmac`] checks the HMAC over this range."
+    )]
+    pub fn hmac_covered_range(&self, entry_offset: usize) -> Range<usize> {
+        entry_offset..entry_offset.saturating_add(self.size as usize)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// Errors which may occur when parsing an APOB blob
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ApobError {
+    /// The buffer is too small to contain the named structure
+    Truncated {
+        name: &'static str,
+        needed: usize,
+        got: usize,
+    },
+    /// The buffer starts `offset` bytes past an address suitably aligned for
+    /// the named structure
+    ///
+    /// Every structure in this crate is packed, so this only happens for
+    /// caller-provided types passed to [`parse_prefix`] or [`payload_as`].
+    Misaligned { name: &'static str, offset: usize },
+    /// The signature is not [`APOB_SIG`]
+    BadSignature([u8; 4]),
+    /// The signature is [`APOB_SIG_SWAPPED`], so the blob has probably been
+    /// byte-swapped in 32-bit words
+    ///
+    /// With the `swap` feature, `ApobOwned::from_swapped` can undo this.
+    ByteSwapped,
+    /// The version is not [`APOB_VERSION`]
+    BadVersion(u32),
+    /// An offset or size points outside of the buffer
+    OffsetOutOfBounds { offset: usize, len: usize },
+    /// A count or length field exceeds the capacity of its array
+    InvalidCount { count: usize, max: usize },
+    /// An entry extends past the size declared in the header
+    EntryPastEnd {
+        offset: usize,
+        end: usize,
+        size: usize,
+    },
+    /// Non-entry data follows the last entry
+    TrailingData { offset: usize, len: usize },
+    /// An SMBIOS string set starting at this offset has no terminator
+    UnterminatedStrings { offset: usize },
+}
+
+impl core::fmt::Display for ApobError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            ApobError::Truncated { name, needed, got } => write!(
+                f,
+                "truncated {name}: needed {needed:#x} bytes, got {got:#x}"
+            ),
+            ApobError::Misaligned { name, offset } => write!(
+                f,
+                "misaligned {name}: buffer starts {offset:#x} bytes past an \
+                 aligned address"
+            ),
+            ApobError::BadSignature(sig) => write!(f, "bad signature {sig:x?}"),
+            ApobError::ByteSwapped => {
+                write!(f, "signature is byte-swapped (BOPA instead of APOB)")
+            }
+            ApobError::BadVersion(v) => write!(f, "unsupported version {v:#x}"),
+            ApobError::OffsetOutOfBounds { offset, len } => write!(
+                f,
+                "offset {offset:#x} is out of bounds for length {len:#x}"
+            ),
+            ApobError::InvalidCount { count, max } => {
+                write!(f, "count {count} exceeds maximum of {max}")
+            }
+            ApobError::EntryPastEnd { offset, end, size } => write!(
+                f,
+                "entry at {offset:#x} ends at {end:#x}, \
+                 past the declared size {size:#x}"
+            ),
+            ApobError::TrailingData { offset, len } => {
+                write!(f, "{len:#x} bytes of trailing data at {offset:#x}")
+            }
+            ApobError::UnterminatedStrings { offset } => {
+                write!(f, "unterminated string set at {offset:#x}")
+            }
+        }
+    }
+}
+
+impl core::error::Error for ApobError {}
+
+/// Reads a `T` from the start of `data`, returning it and the remaining bytes
+///
+/// If `data` is too short, the error names `T` and says how many bytes were
+/// needed.  If `T` isn't packed and `data` isn't aligned for it, the error is
+/// [`ApobError::Misaligned`].
+pub fn parse_prefix<T: FromBytes + KnownLayout + Immutable>(
+    data: &[u8],
+) -> Result<(&T, &[u8]), ApobError> {
+    T::ref_from_prefix(data).map_err(|e| match e {
+        ConvertError::Alignment(_) => ApobError::Misaligned {
+            name: short_name::<T>(),
+            offset: data.as_ptr() as usize % core::mem::align_of::<T>(),
+        },
+        ConvertError::Size(_) => truncated::<T>(data),
+        ConvertError::Validity(never) => match never {},
+    })
+}
+
+/// Casts the start of an entry's payload to a `T`, ignoring trailing bytes
+///
+/// This works for any `repr(C)` payload type, including ones this crate
+/// doesn't describe. Use [`parse_prefix`] to also get the remaining bytes.
+pub fn payload_as<T: FromBytes + KnownLayout + Immutable>(
+    data: &[u8],
+) -> Result<&T, ApobError> {
+    parse_prefix(data).map(|(t, _)| t)
+}
+
+/// Copies a `T` from the start of `data`, which needn't be aligned
+fn read_prefix<T: FromBytes>(data: &[u8]) -> Result<T, ApobError> {
+    T::read_from_prefix(data)
+        .map(|(t, _)| t)
+        .map_err(|_| truncated::<T>(data))
+}
+
+fn truncated<T>(data: &[u8]) -> ApobError {
+    ApobError::Truncated {
+        name: short_name::<T>(),
+        needed: core::mem::size_of::<T>(),
+        got: data.len(),
+    }
+}
+
+/// Returns the name of `T` without its module path, for errors
+fn short_name<T>() -> &'static str {
+    let name = core::any::type_name::<T>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Parsed APOB blob, borrowing from an underlying buffer
+#[derive(Copy, Clone, Debug)]
+pub struct Apob<'a> {
+    header: &'a ApobHeader,
+    data: &'a [u8],
+}
+
+impl<'a> Apob<'a> {
+    /// Parses the header at the start of the buffer and checks it with
+    /// [`ApobHeader::validate`]
+    ///
+    /// An `offset` which points inside the header, including zero, is
+    /// rejected.
+    pub fn parse(data: &'a [u8]) -> Result<Self, ApobError> {
+        Self::parse_inner(data, true)
+    }
+
+    /// Parses the header like [`Apob::parse`], but accepts any version
+    ///
+    /// Entry layouts are only known for [`APOB_VERSION`], so anything read
+    /// from a blob with another version may be garbage; this is for
+    /// investigating new firmware, not for production use.
+    pub fn parse_any_version(data: &'a [u8]) -> Result<Self, ApobError> {
+        Self::parse_inner(data, false)
+    }
+
+    fn parse_inner(
+        data: &'a [u8],
+        check_version: bool,
+    ) -> Result<Self, ApobError> {
+        let (header, _) = parse_prefix::<ApobHeader>(data)?;
+        header.check(data.len(), check_version).inspect_err(|_e| {
+            event!(debug, error = %_e, "header rejected");
+        })?;
+        event!(
+            trace,
+            version = { header.version },
+            size = { header.size },
+            offset = { header.offset },
+            "header validated"
+        );
+        Ok(Self { header, data })
+    }
+
+    /// Returns the blob header
+    pub fn header(&self) -> &'a ApobHeader {
+        self.header
+    }
+
+    /// Returns the full underlying buffer
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the offset of the first entry, from the header
+    ///
+    /// Parsing has checked that this is past the header and within the
+    /// buffer.
+    pub fn entries_offset(&self) -> usize {
+        self.header.offset as usize
+    }
+
+    /// Returns the bytes between the header and the first entry
+    pub fn padding(&self) -> &'a [u8] {
+        let start = core::mem::size_of::<ApobHeader>();
+        self.data.get(start..self.entries_offset()).unwrap_or(&[])
+    }
+
+    /// Checks whether the padding region is entirely zero
+    ///
+    /// Non-zero padding may indicate a corrupted or misaligned blob.
+    pub fn padding_is_zero(&self) -> bool {
+        self.padding().iter().all(|b| *b == 0)
+    }
+
+    /// Iterates over entries, yielding each header and its trailing data
+    pub fn entries(&self) -> ApobEntries<'a> {
+        self.entries_from(self.entries_offset())
+    }
+
+    /// Iterates over entries, starting with the one at `offset`
+    ///
+    /// This lets a caller walk entries in stages without holding on to an
+    /// iterator; `offset` should be [`Apob::entries_offset`] or the end of a
+    /// previous entry, since anything else will be misparsed.
+    pub fn entries_from(&self, offset: usize) -> ApobEntries<'a> {
+        ApobEntries {
+            data: self.data,
+            pos: offset,
+        }
+    }
+
+    /// Iterates over cancelled entries, skipping all others
+    ///
+    /// Errors are passed through, as with [`Apob::entries`].
+    pub fn cancelled_entries(
+        &self,
+    ) -> impl Iterator<Item = Result<(&'a ApobEntry, &'a [u8]), ApobError>>
+    {
+        self.entries().filter(|e| match e {
+            Ok((entry, _)) => entry.cancelled(),
+            Err(_) => true,
+        })
+    }
+
+    /// Iterates over entry headers, without slicing out their data
+    ///
+    /// Iteration stops after the first error, as with [`Apob::entries`].
+    pub fn iter_headers(
+        &self,
+    ) -> impl Iterator<Item = Result<&'a ApobEntry, ApobError>> {
+        self.entries().map(|e| e.map(|(entry, _)| entry))
+    }
+
+    /// Counts entries by walking their headers
+    ///
+    /// Payloads are skipped over using each entry's `size`, so this is cheap
+    /// even for large blobs.
+    pub fn entry_count(&self) -> Result<usize, ApobError> {
+        self.iter_headers().try_fold(0, |n, e| e.map(|_| n + 1))
+    }
+
+    /// Finds the entry whose bytes, including its header, contain `offset`
+    ///
+    /// Returns `None` if `offset` lands in the blob header, the padding, or
+    /// anything past the size declared in the header, or if the entries
+    /// before it are malformed.
+    pub fn entry_at_offset(
+        &self,
+        offset: usize,
+    ) -> Option<(&'a ApobEntry, &'a [u8])> {
+        let declared = self.header.size as usize;
+        let mut pos = self.entries_offset();
+        if offset < pos || offset >= declared {
+            return None;
+        }
+        for e in self.entries() {
+            let (entry, data) = e.ok()?;
+            // The iterator has already checked that this fits
+            let end = pos + entry.size as usize;
+            if offset < end {
+                return Some((entry, data));
+            }
+            pos = end;
+        }
+        None
+    }
+
+    /// Checks that entries are laid end-to-end and fill the declared size
+    ///
+    /// Returns every anomaly found: a buffer shorter than the declared size,
+    /// entries which are malformed or extend past the declared size, and data
+    /// following the last entry.
+    #[cfg(feature = "alloc")]
+    pub fn validate_layout(&self) -> Result<(), alloc::vec::Vec<ApobError>> {
+        let mut errors = alloc::vec::Vec::new();
+        let declared = self.header.size as usize;
+        if declared > self.data.len() {
+            errors.push(ApobError::Truncated {
+                name: "blob",
+                needed: declared,
+                got: self.data.len(),
+            });
+        }
+        let mut pos = self.entries_offset();
+        for e in self.entries() {
+            if pos >= declared {
+                break;
+            }
+            match e {
+                Ok((entry, _)) => {
+                    // The iterator has already checked that this fits
+                    let end = pos + entry.size as usize;
+                    if end > declared {
+                        errors.push(ApobError::EntryPastEnd {
+                            offset: pos,
+                            end,
+                            size: declared,
+                        });
+                    }
+                    pos = end;
+                }
+                Err(e) => {
+                    errors.push(e);
+                    pos = self.data.len();
+                }
+            }
+        }
+        let end = pos.max(declared);
+        if end < self.data.len() {
+            errors.push(ApobError::TrailingData {
+                offset: end,
+                len: self.data.len() - end,
+            });
+        }
+        #[cfg(feature = "tracing")]
+        for e in &errors {
+            event!(warn, error = %e, "layout anomaly");
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Iterates over non-cancelled entries matching a group and type
+    ///
+    /// If `inst` is provided, only entries with that instance are returned.
+    /// Cancellation bits in an entry's type are ignored when comparing it
+    /// against `ty`.  Parse errors encountered while walking the blob are
+    /// passed through.
+    pub fn find(
+        &self,
+        group: ApobGroup,
+        ty: u32,
+        inst: Option<u32>,
+    ) -> impl Iterator<Item = Result<(&'a ApobEntry, &'a [u8]), ApobError>>
+    {
+        self.entries().filter(move |e| match e {
+            Ok((entry, _)) => {
+                !entry.cancelled()
+                    && entry.group() == Some(group)
+                    && entry.type_id() == ty
+                    && (inst.is_none() || inst == Some(entry.inst))
+            }
+            Err(_) => true,
+        })
+    }
+
+    /// Finds the first non-cancelled entry with the given group and type
+    fn find_typed(
+        &self,
+        group: ApobGroup,
+        ty: u32,
+    ) -> Option<Result<&'a [u8], ApobError>> {
+        self.find(group, ty, None)
+            .next()
+            .map(|e| e.map(|(_, data)| data))
+    }
+
+    /// Returns the events from the event log, if present
+    pub fn event_log(&self) -> Option<Result<&'a [ApobEvent], ApobError>> {
+        let data = self.find_typed(
+            ApobGroup::GENERAL,
+            ApobGeneralType::EVENT_LOG as u32,
+        )?;
+        Some(data.and_then(event_log))
+    }
+
+    /// Returns the Milan CCX core map, if present
+    pub fn ccx_coremap(
+        &self,
+    ) -> Option<Result<&'a MilanApobCoremap, ApobError>> {
+        let data =
+            self.find_typed(ApobGroup::CCX, ApobCcxType::MILAN_COREMAP as u32)?;
+        Some(data.and_then(payload_as))
+    }
+
+    /// Returns the valid bytes of the Milan fabric PHY override, if present
+    pub fn phy_override(&self) -> Option<Result<&'a [u8], ApobError>> {
+        let data = self.find_typed(
+            ApobGroup::FABRIC,
+            ApobFabricType::MILAN_FABRIC_PHY_OVERRIDE as u32,
+        )?;
+        Some(data.and_then(|data| {
+            let p = payload_as::<MilanApobPhyOverride>(data)?;
+            p.data()
+        }))
+    }
+}
+
+/// Iterator over the entries of an [`Apob`]
+///
+/// Iteration stops after the first error.
+#[derive(Clone, Debug)]
+pub struct ApobEntries<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Iterator for ApobEntries<'a> {
+    type Item = Result<(&'a ApobEntry, &'a [u8]), ApobError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.data.len() {
+            return None;
+        }
+        let rest = &self.data[self.pos..];
+        let hdr_size = core::mem::size_of::<ApobEntry>();
+        let out = parse_prefix::<ApobEntry>(rest).and_then(|(entry, _)| {
+            // `size` comes straight from the blob, so this could wrap on
+            // targets with a 32-bit `usize`
+            let size = usize::try_from(entry.size).unwrap_or(usize::MAX);
+            match self.pos.checked_add(size) {
+                Some(end) if size >= hdr_size && end <= self.data.len() => {
+                    event!(
+                        trace,
+                        offset = self.pos,
+                        group = { entry.group },
+                        ty = { entry.ty },
+                        inst = { entry.inst },
+                        size,
+                        "entry"
+                    );
+                    self.pos = end;
+                    Ok((entry, &rest[hdr_size..size]))
+                }
+                _ => Err(ApobError::OffsetOutOfBounds {
+                    offset: self.pos.saturating_add(size),
+                    len: self.data.len(),
+                }),
+            }
+        });
+        // Only read by the event, which may be compiled out
+        if let Err(_e) = &out {
+            event!(debug, offset = self.pos, error = %_e, "malformed entry");
+            self.pos = self.data.len();
+        }
+        Some(out)
+    }
+}
+
+/// Iterator over APOB images which are concatenated in a single buffer
+///
+/// Each image starts at an [`APOB_SIG`] and is limited to the size declared in
+/// its header, after which we look for the next signature.  If the declared
+/// size is too small or runs off the end of the buffer, the image is instead
+/// assumed to end where the next signature begins.  Bytes which aren't part
+/// of an image (such as padding between images) are skipped.
+#[derive(Clone, Debug)]
+pub struct MultiApob<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> MultiApob<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Finds the next signature at or after `pos`
+    fn find_sig(&self, pos: usize) -> Option<usize> {
+        self.data
+            .get(pos..)?
+            .windows(APOB_SIG.len())
+            .position(|w| w == APOB_SIG)
+            .map(|i| pos + i)
+    }
+}
+
+impl<'a> Iterator for MultiApob<'a> {
+    type Item = Result<Apob<'a>, ApobError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.find_sig(self.pos)?;
+        let rest = &self.data[start..];
+        let (header, _) = match parse_prefix::<ApobHeader>(rest) {
+            Ok(h) => h,
+            Err(e) => {
+                self.pos = self.data.len();
+                return Some(Err(e));
+            }
+        };
+        let size = usize::try_from(header.size).unwrap_or(usize::MAX);
+        let end = match start.checked_add(size) {
+            Some(end)
+                if size >= core::mem::size_of::<ApobHeader>()
+                    && end <= self.data.len() =>
+            {
+                end
+            }
+            _ => self
+                .find_sig(start + APOB_SIG.len())
+                .unwrap_or(self.data.len()),
+        };
+        event!(debug, start, end, "image");
+        // Always make progress, even if the image is rejected, so that a
+        // corrupt header doesn't hide the images after it
+        self.pos = end;
+        Some(Apob::parse(&self.data[start..end]))
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
 // GENERAL group handling
 
-#[derive(Copy, Clone, Debug, FromRepr)]
+#[derive(Copy, Clone, Debug, FromRepr, IntoStaticStr)]
 #[allow(non_camel_case_types)]
 pub enum ApobGeneralType {
     EVENT_LOG = 6,
@@ -77,23 +816,154 @@ pub enum ApobGeneralType {
 
 /// [`ApobGroup::GENERAL`] + [`ApobGeneralType::EVENT_LOG`]
 #[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
-#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C, packed)]
 pub struct MilanApobEventLog {
     pub count: u16,
+    #[cfg_attr(feature = "serde", serde(skip))]
     _pad: u16,
-    pub events: [MilanApobEvent; 64],
+    #[cfg_attr(feature = "serde", serde(serialize_with = "ser::array"))]
+    pub events: [ApobEvent; 64],
+}
+
+// Count and padding, then 64 events
+const _: () = assert!(core::mem::size_of::<MilanApobEventLog>() == 4 + 64 * 16);
+const _: () = assert!(core::mem::align_of::<MilanApobEventLog>() == 1);
+
+impl TryFrom<&[u8]> for MilanApobEventLog {
+    type Error = ApobError;
+
+    /// Copies the log from the start of `data`, checking its `count`
+    fn try_from(data: &[u8]) -> Result<Self, ApobError> {
+        let log = read_prefix::<Self>(data)?;
+        if usize::from(log.count) > log.events.len() {
+            return Err(ApobError::InvalidCount {
+                count: log.count.into(),
+                max: log.events.len(),
+            });
+        }
+        Ok(log)
+    }
+}
+
+impl MilanApobEventLog {
+    /// Returns the valid events which are at least as severe as `class`
+    ///
+    /// `count` is clamped to the size of the log.
+    pub fn events_at_least(
+        &self,
+        class: MilanApobEventClass,
+    ) -> impl Iterator<Item = &ApobEvent> {
+        let n = usize::from(self.count).min(self.events.len());
+        self.events[..n]
+            .iter()
+            .filter(move |e| e.is_at_least(class))
+    }
+
+    /// Returns the valid events with their class and info code resolved
+    ///
+    /// `count` is clamped to the size of the log.
+    pub fn decoded(&self) -> impl Iterator<Item = DecodedEvent> + '_ {
+        let n = usize::from(self.count).min(self.events.len());
+        self.events[..n].iter().map(DecodedEvent::from)
+    }
 }
 
+/// Single event log record
 #[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
-#[repr(C)]
-pub struct MilanApobEvent {
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C, packed)]
+pub struct ApobEvent {
     pub class: u32,
     pub info: u32,
     pub data0: u32,
     pub data1: u32,
 }
 
-#[derive(Copy, Clone, Debug, FromRepr)]
+// Four `u32`s, 16 bytes
+const _: () = assert!(core::mem::size_of::<ApobEvent>() == 16);
+const _: () = assert!(core::mem::align_of::<ApobEvent>() == 1);
+
+impl ApobEvent {
+    /// Looks up the class of this event
+    pub fn class(&self) -> Option<MilanApobEventClass> {
+        MilanApobEventClass::from_repr(self.class as usize)
+    }
+
+    /// Checks whether this event is at least as severe as `class`
+    ///
+    /// This compares raw values, so an unknown class above
+    /// [`MilanApobEventClass::FATAL`] counts as more severe than any known
+    /// class.
+    pub fn is_at_least(&self, class: MilanApobEventClass) -> bool {
+        self.class >= class as u32
+    }
+}
+
+/// Former name of [`ApobEvent`]
+pub type MilanApobEvent = ApobEvent;
+
+/// Milan event with its class and info code resolved
+///
+/// Unknown classes and info codes are kept as their raw values.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DecodedEvent {
+    pub class: Result<MilanApobEventClass, u32>,
+    pub info: Result<MilanApobEventInfo, u32>,
+    pub data0: u32,
+    pub data1: u32,
+    /// Details of a [`MilanApobEventInfo::TRAIN_ERROR`], taken from the data
+    /// words
+    pub train_error: Option<MilanTrainError>,
+}
+
+impl From<&ApobEvent> for DecodedEvent {
+    fn from(e: &ApobEvent) -> Self {
+        let info = MilanApobEventInfo::from_repr(e.info as usize);
+        let train_error = match info {
+            Some(MilanApobEventInfo::TRAIN_ERROR) => {
+                let data0 = MilanTrainErrorData0(e.data0);
+                let data1 = MilanTrainErrorData1(e.data1);
+                Some(MilanTrainError {
+                    sock: data0.sock(),
+                    chan: data0.chan(),
+                    dimm: data0.dimm(),
+                    rank: data0.rank(),
+                    pmu_load: data1.pmu_load(),
+                    pmu_train: data1.pmu_train(),
+                })
+            }
+            None => None,
+        };
+        Self {
+            class: e.class().ok_or(e.class),
+            info: info.ok_or(e.info),
+            data0: e.data0,
+            data1: e.data1,
+            train_error,
+        }
+    }
+}
+
+/// Fields of a Milan training error, from [`MilanTrainErrorData0`] and
+/// [`MilanTrainErrorData1`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MilanTrainError {
+    pub sock: u32,
+    pub chan: u32,
+    pub dimm: u32,
+    pub rank: u32,
+    pub pmu_load: bool,
+    pub pmu_train: bool,
+}
+
+/// Milan event classes
+///
+/// Classes are ordered by severity, which matches their raw value; variants
+/// must stay in ascending order for the derived `Ord` to agree.
+#[derive(
+    Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, FromRepr, IntoStaticStr,
+)]
 #[allow(non_camel_case_types)]
 pub enum MilanApobEventClass {
     ALERT = 5,
@@ -103,7 +973,16 @@ pub enum MilanApobEventClass {
     FATAL = 9,
 }
 
-#[derive(Copy, Clone, Debug, FromRepr)]
+impl MilanApobEventClass {
+    /// Looks up a class by its name, ignoring case
+    pub fn from_name(name: &str) -> Option<Self> {
+        (0..=u8::MAX as usize)
+            .filter_map(Self::from_repr)
+            .find(|c| <&str>::from(c).eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, FromRepr, IntoStaticStr)]
 #[allow(non_camel_case_types)]
 pub enum MilanApobEventInfo {
     TRAIN_ERROR = 0x4001,
@@ -141,14 +1020,42 @@ impl MilanTrainErrorData1 {
     }
 }
 
+/// Decoders for the data of Milan events, keyed by info code
+///
+/// Only [`MilanApobEventInfo::TRAIN_ERROR`] is described so far; other codes
+/// are added here once the meaning of their data words can be sourced.
+#[cfg(feature = "alloc")]
+const MILAN_EVENT_DATA: &[(MilanApobEventInfo, EventDataDecoder)] =
+    &[(MilanApobEventInfo::TRAIN_ERROR, milan_train_error)];
+
+#[cfg(feature = "alloc")]
+fn milan_train_error(
+    data0: u32,
+    data1: u32,
+) -> alloc::vec::Vec<alloc::string::String> {
+    use alloc::format;
+    let data0 = MilanTrainErrorData0(data0);
+    let data1 = MilanTrainErrorData1(data1);
+    let mut out = alloc::vec![
+        format!("sock: {}  chan: {}", data0.sock(), data0.chan()),
+        format!("dimm: {}  rank: {}", data0.dimm(), data0.rank()),
+    ];
+    if data1.pmu_load() {
+        out.push("PMU load error".into());
+    }
+    if data1.pmu_train() {
+        out.push("PMU train error".into());
+    }
+    out
+}
+
 ////////////////////////////////////////////////////////////////////////////////
-// FABRIC group handling
+// CCX group handling
 
-#[derive(Copy, Clone, Debug, FromRepr)]
+#[derive(Copy, Clone, Debug, FromRepr, IntoStaticStr)]
 #[allow(non_camel_case_types)]
-pub enum ApobFabricType {
-    SYS_MEM_MAP = 9,
-    MILAN_FABRIC_PHY_OVERRIDE = 21,
+pub enum ApobCcxType {
+    MILAN_COREMAP = 3,
 }
 
 const MILAN_APOB_CCX_MAX_CCDS: usize = 8;
@@ -156,62 +1063,216 @@ const MILAN_APOB_CCX_MAX_CCXS: usize = 2;
 const MILAN_APOB_CCX_MAX_CORES: usize = 8;
 const MILAN_APOB_CCX_MAX_THREADS: usize = 2;
 
-/// [`ApobGroup::FABRIC`] + [`ApobFabricType::SYS_MEM_MAP`]
+/// [`ApobGroup::CCX`] + [`ApobCcxType::MILAN_COREMAP`]
 #[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
-#[repr(C)]
-pub struct ApobSysMemMap {
-    /// Physical address of the upper limit (exclusive) of available RAM
-    pub high_phys: u64,
-
-    /// Number of [`ApobSysMemMapHole`] entries following this structure
-    pub hole_count: u32,
-    _padding: u32,
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C, packed)]
+pub struct MilanApobCoremap {
+    pub ccds: [MilanApobCcd; MILAN_APOB_CCX_MAX_CCDS],
 }
 
-#[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
-#[repr(C)]
-pub struct ApobSysMemMapHole {
-    /// Base physical address of this hole
-    pub base: u64,
-
-    /// Size of the hole in bytes
-    pub size: u64,
+// 8 CCDs of 51 bytes, 408 bytes
+const _: () = assert!(core::mem::size_of::<MilanApobCoremap>() == 8 * 51);
+const _: () = assert!(core::mem::align_of::<MilanApobCoremap>() == 1);
 
-    /// Tag indicating the purpose of this hole
-    ///
-    /// The specific values may vary between different microarchitectures and/or
-    /// firmware.
-    pub ty: u32,
-    _padding: u32,
-}
+impl TryFrom<&[u8]> for MilanApobCoremap {
+    type Error = ApobError;
 
-#[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
-#[repr(C, packed)]
-pub struct MilanApobCoremap {
-    pub ccds: [MilanApobCcd; MILAN_APOB_CCX_MAX_CCDS],
+    /// Copies the core map from the start of `data`
+    fn try_from(data: &[u8]) -> Result<Self, ApobError> {
+        read_prefix(data)
+    }
 }
 
 #[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C, packed)]
 pub struct MilanApobCcd {
     pub macd_id: u8,
     pub macd_ccxs: [MilanApobCcx; MILAN_APOB_CCX_MAX_CCXS],
 }
 
+// ID, then 2 CCXs of 25 bytes
+const _: () = assert!(core::mem::size_of::<MilanApobCcd>() == 1 + 2 * 25);
+const _: () = assert!(core::mem::align_of::<MilanApobCcd>() == 1);
+
 #[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C, packed)]
 pub struct MilanApobCcx {
     pub macx_id: u8,
     pub macx_cores: [MilanApobCore; MILAN_APOB_CCX_MAX_CORES],
 }
 
+// ID, then 8 cores of 3 bytes
+const _: () = assert!(core::mem::size_of::<MilanApobCcx>() == 1 + 8 * 3);
+const _: () = assert!(core::mem::align_of::<MilanApobCcx>() == 1);
+
 #[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[repr(C, packed)]
 pub struct MilanApobCore {
     pub mac_id: u8,
     pub mac_thread_exists: [u8; MILAN_APOB_CCX_MAX_THREADS],
 }
 
+// ID, then 2 thread flags
+const _: () = assert!(core::mem::size_of::<MilanApobCore>() == 3);
+const _: () = assert!(core::mem::align_of::<MilanApobCore>() == 1);
+
+impl MilanApobCoremap {
+    /// Iterates over cores with at least one populated thread
+    ///
+    /// Yields `(ccd, ccx, core)` tuples
+    pub fn present_cores(
+        &self,
+    ) -> impl Iterator<Item = (&MilanApobCcd, &MilanApobCcx, &MilanApobCore)>
+    {
+        self.ccds.iter().flat_map(|ccd| {
+            ccd.macd_ccxs.iter().flat_map(move |ccx| {
+                ccx.macx_cores
+                    .iter()
+                    .filter(|core| core.present())
+                    .map(move |core| (ccd, ccx, core))
+            })
+        })
+    }
+}
+
+impl MilanApobCore {
+    /// Checks whether any thread on this core exists
+    pub fn present(&self) -> bool {
+        self.mac_thread_exists.iter().any(|t| *t != 0)
+    }
+
+    /// Iterates over the indices of threads which exist on this core
+    pub fn threads(&self) -> impl Iterator<Item = usize> + '_ {
+        self.mac_thread_exists
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| **t != 0)
+            .map(|(i, _)| i)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// FABRIC group handling
+
+#[derive(Copy, Clone, Debug, FromRepr, IntoStaticStr)]
+#[allow(non_camel_case_types)]
+pub enum ApobFabricType {
+    SYS_MEM_MAP = 9,
+    MILAN_FABRIC_PHY_OVERRIDE = 21,
+}
+
+/// [`ApobGroup::FABRIC`] + [`ApobFabricType::SYS_MEM_MAP`]
+///
+/// This is packed because entry payloads are only 4-byte aligned in the blob.
+#[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C, packed)]
+pub struct ApobSysMemMap {
+    /// Physical address of the upper limit (exclusive) of available RAM
+    pub high_phys: u64,
+
+    /// Number of [`ApobSysMemMapHole`] entries following this structure
+    pub hole_count: u32,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _padding: u32,
+}
+
+// Limit, count, and padding, 16 bytes
+const _: () = assert!(core::mem::size_of::<ApobSysMemMap>() == 16);
+const _: () = assert!(core::mem::align_of::<ApobSysMemMap>() == 1);
+
+impl TryFrom<&[u8]> for ApobSysMemMap {
+    type Error = ApobError;
+
+    /// Copies the map from the start of `data`, checking that `data` is long
+    /// enough to hold `hole_count` holes after it
+    fn try_from(data: &[u8]) -> Result<Self, ApobError> {
+        let map = read_prefix::<Self>(data)?;
+        let max = (data.len() - core::mem::size_of::<Self>())
+            / core::mem::size_of::<ApobSysMemMapHole>();
+        if map.hole_count as usize > max {
+            return Err(ApobError::InvalidCount {
+                count: map.hole_count as usize,
+                max,
+            });
+        }
+        Ok(map)
+    }
+}
+
+impl ApobSysMemMap {
+    /// Returns `(base, len)` for each range of RAM which isn't in a hole
+    ///
+    /// Holes may be unsorted or overlapping, and ranges are clipped to
+    /// [`high_phys`](Self::high_phys).
+    pub fn usable_ranges<'a>(
+        &self,
+        holes: &'a [ApobSysMemMapHole],
+    ) -> impl Iterator<Item = (u64, u64)> + 'a {
+        let high_phys = self.high_phys;
+        let holes = holes.iter().filter(|h| h.size > 0);
+        let end = |h: &ApobSysMemMapHole| h.base.saturating_add(h.size);
+        let mut pos = 0;
+        core::iter::from_fn(move || {
+            // Skip past any holes covering the current position, repeating
+            // in case they overlap
+            while let Some(h) = holes
+                .clone()
+                .filter(|h| h.base <= pos && end(h) > pos)
+                .max_by_key(|h| end(h))
+            {
+                pos = end(h);
+            }
+            if pos >= high_phys {
+                return None;
+            }
+            let next = holes
+                .clone()
+                .map(|h| h.base)
+                .filter(|b| *b > pos)
+                .min()
+                .unwrap_or(high_phys)
+                .min(high_phys);
+            let out = (pos, next - pos);
+            pos = next;
+            Some(out)
+        })
+    }
+
+    /// Returns the number of bytes of RAM below `high_phys` which aren't in a
+    /// hole, counting overlapping holes once
+    pub fn usable_bytes(&self, holes: &[ApobSysMemMapHole]) -> u64 {
+        self.usable_ranges(holes).map(|(_, len)| len).sum()
+    }
+}
+
+#[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C, packed)]
+pub struct ApobSysMemMapHole {
+    /// Base physical address of this hole
+    pub base: u64,
+
+    /// Size of the hole in bytes
+    pub size: u64,
+
+    /// Tag indicating the purpose of this hole
+    ///
+    /// The specific values may vary between different microarchitectures and/or
+    /// firmware.
+    pub ty: u32,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _padding: u32,
+}
+
+// Base, size, tag, and padding, 24 bytes
+const _: () = assert!(core::mem::size_of::<ApobSysMemMapHole>() == 24);
+const _: () = assert!(core::mem::align_of::<ApobSysMemMapHole>() == 1);
+
 /// [`ApobGroup::FABRIC`] + [`ApobFabricType::MILAN_FABRIC_PHY_OVERRIDE`]
 #[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
 #[repr(C, packed)]
@@ -220,17 +1281,28 @@ pub struct MilanApobPhyOverride {
     pub map_data: [u8; 256],
 }
 
+impl MilanApobPhyOverride {
+    /// Returns the first `map_datalen` bytes of `map_data`
+    pub fn data(&self) -> Result<&[u8], ApobError> {
+        let len = self.map_datalen as usize;
+        self.map_data.get(..len).ok_or(ApobError::InvalidCount {
+            count: len,
+            max: self.map_data.len(),
+        })
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // MEMORY group
 
-#[derive(Copy, Clone, Debug, FromRepr)]
+#[derive(Copy, Clone, Debug, FromRepr, IntoStaticStr)]
 #[allow(non_camel_case_types)]
 pub enum ApobMemoryType {
     MILAN_PMU_TRAIN_FAIL = 22,
 }
 
 #[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
-#[repr(C)]
+#[repr(C, packed)]
 pub struct PmuTfiEntryBitfield(pub u32);
 
 impl PmuTfiEntryBitfield {
@@ -256,18 +1328,184 @@ impl PmuTfiEntryBitfield {
 
 /// A single training error entry
 #[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
-#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C, packed)]
 pub struct PmuTfiEntry {
     pub bits: PmuTfiEntryBitfield,
     pub error: u32,
     pub data: [u32; 4],
 }
 
+// Six `u32`s, 24 bytes
+const _: () = assert!(core::mem::size_of::<PmuTfiEntry>() == 24);
+const _: () = assert!(core::mem::align_of::<PmuTfiEntry>() == 1);
+
 /// A set of training failure entries
 #[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
-#[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C, packed)]
 pub struct PmuTfi {
     /// Position of the next valid entry
     pub nvalid: u32,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "ser::array"))]
     pub entries: [PmuTfiEntry; 40],
 }
+
+// Count, then 40 entries of 24 bytes
+const _: () = assert!(core::mem::size_of::<PmuTfi>() == 4 + 40 * 24);
+const _: () = assert!(core::mem::align_of::<PmuTfi>() == 1);
+
+impl TryFrom<&[u8]> for PmuTfi {
+    type Error = ApobError;
+
+    /// Copies the failure set from the start of `data`, checking its `nvalid`
+    fn try_from(data: &[u8]) -> Result<Self, ApobError> {
+        let tfi = read_prefix::<Self>(data)?;
+        if tfi.nvalid as usize > tfi.entries.len() {
+            return Err(ApobError::InvalidCount {
+                count: tfi.nvalid as usize,
+                max: tfi.entries.len(),
+            });
+        }
+        Ok(tfi)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// SMBIOS group
+
+/// Header of each structure in the SMBIOS table, as laid out in DMTF DSP0134
+/// section 6.1.2
+#[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C, packed)]
+pub struct SmbiosHeader {
+    pub ty: u8,
+    /// Length of the formatted area, including this header
+    pub length: u8,
+    pub handle: u16,
+}
+
+// Type, length, and handle, 4 bytes
+const _: () = assert!(core::mem::size_of::<SmbiosHeader>() == 4);
+const _: () = assert!(core::mem::align_of::<SmbiosHeader>() == 1);
+
+/// A single SMBIOS structure, borrowing from an [`ApobGroup::SMBIOS`] entry
+#[derive(Copy, Clone, Debug)]
+pub struct SmbiosStructure<'a> {
+    pub header: &'a SmbiosHeader,
+    /// Formatted area following the header
+    pub formatted: &'a [u8],
+    /// Unformatted string set, including its double-NUL terminator
+    strings: &'a [u8],
+}
+
+impl<'a> SmbiosStructure<'a> {
+    /// Returns the structure's strings
+    ///
+    /// The formatted area refers to these by 1-based index.
+    pub fn strings(&self) -> impl Iterator<Item = &'a [u8]> + 'a {
+        self.strings
+            .split(|b| *b == 0)
+            .take_while(|s| !s.is_empty())
+    }
+}
+
+/// Iterator over the SMBIOS structures in an [`ApobGroup::SMBIOS`] entry
+///
+/// We have no source for how types within the group are numbered, so the entry
+/// alone doesn't say whether it holds structures; a payload which doesn't will
+/// fail to parse.
+///
+/// Iteration stops at the end of the data, at all-zero padding, or after the
+/// first error.
+#[derive(Clone, Debug)]
+pub struct SmbiosStructures<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> SmbiosStructures<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+}
+
+impl<'a> Iterator for SmbiosStructures<'a> {
+    type Item = Result<SmbiosStructure<'a>, ApobError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.data[self.pos..];
+        if rest.iter().all(|b| *b == 0) {
+            return None;
+        }
+        let hdr_size = core::mem::size_of::<SmbiosHeader>();
+        let out = parse_prefix::<SmbiosHeader>(rest).and_then(|(header, _)| {
+            let len = header.length as usize;
+            if len < hdr_size || len > rest.len() {
+                return Err(ApobError::OffsetOutOfBounds {
+                    offset: self.pos + len,
+                    len: self.data.len(),
+                });
+            }
+            // The string set ends with two NULs, even if it's empty
+            match rest[len..].windows(2).position(|w| w == [0, 0]) {
+                Some(end) => {
+                    self.pos += len + end + 2;
+                    Ok(SmbiosStructure {
+                        header,
+                        formatted: &rest[hdr_size..len],
+                        strings: &rest[len..][..end + 2],
+                    })
+                }
+                None => Err(ApobError::UnterminatedStrings {
+                    offset: self.pos + len,
+                }),
+            }
+        });
+        if out.is_err() {
+            self.pos = self.data.len();
+        }
+        Some(out)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// APCB group
+
+/// Signature at the start of an APCB (AMD PSP Customization Block)
+pub const APCB_SIG: [u8; 4] = *b"APCB";
+
+/// Header of an APCB blob embedded in an [`ApobGroup::APCB`] entry
+///
+/// This matches `V2_HEADER` in `oxidecomputer/amd-apcb`.  Types within the
+/// group aren't enumerated, so any entry may hold an APCB; the rest of the
+/// APCB is beyond the scope of this crate.
+#[derive(Copy, Clone, Debug, IntoBytes, FromBytes, KnownLayout, Immutable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(C, packed)]
+pub struct ApcbHeader {
+    pub sig: [u8; 4],
+    pub header_size: u16,
+    pub version: u16,
+    /// Size of the whole APCB, including this header
+    pub apcb_size: u32,
+    pub unique_apcb_instance: u32,
+    pub checksum_byte: u8,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _reserved1: [u8; 3],
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _reserved2: [u32; 3],
+}
+
+// Signature, sizes, version, instance, checksum, and reserved, 32 bytes
+const _: () = assert!(core::mem::size_of::<ApcbHeader>() == 32);
+const _: () = assert!(core::mem::align_of::<ApcbHeader>() == 1);
+
+impl ApcbHeader {
+    /// Reads an APCB header from the start of `data`, returning `None` if it
+    /// doesn't begin with [`APCB_SIG`]
+    pub fn parse(data: &[u8]) -> Option<&Self> {
+        payload_as::<Self>(data).ok().filter(|h| h.sig == APCB_SIG)
+    }
+}
@@ -0,0 +1,143 @@
+use crate::{
+    Apob, ApobEntries, ApobEntry, ApobError, ApobEvent, ApobGroup, ApobHeader,
+    MilanApobCoremap,
+};
+
+use alloc::vec::Vec;
+
+/// Parsed APOB blob which owns its buffer
+///
+/// Only the bytes are stored; each accessor re-borrows them through an
+/// [`Apob`], so the object can be moved freely (e.g. across an FFI boundary)
+/// without being tied to the lifetime of the original buffer.
+#[derive(Clone, Debug)]
+pub struct ApobOwned {
+    data: Vec<u8>,
+}
+
+impl ApobOwned {
+    /// Takes ownership of a buffer, checking its header
+    pub fn from_vec(data: Vec<u8>) -> Result<Self, ApobError> {
+        Apob::parse(&data)?;
+        Ok(Self { data })
+    }
+
+    /// Copies a buffer, checking its header
+    pub fn from_slice(data: &[u8]) -> Result<Self, ApobError> {
+        Self::from_vec(data.to_vec())
+    }
+
+    /// Copies a buffer whose 32-bit words have been byte-swapped, swapping
+    /// them back before checking the header
+    ///
+    /// This is the inverse of a transport which swaps every word, so it
+    /// restores byte arrays (like HMACs and SMBIOS strings) as well as integer
+    /// fields.  A partial word at the end of the buffer is left as-is.
+    #[cfg(feature = "swap")]
+    pub fn from_swapped(data: &[u8]) -> Result<Self, ApobError> {
+        let mut data = data.to_vec();
+        for word in data.chunks_exact_mut(4) {
+            word.reverse();
+        }
+        Self::from_vec(data)
+    }
+
+    /// Returns the borrowing view of this blob
+    pub fn as_apob(&self) -> Apob<'_> {
+        // The header was checked in `from_vec` and the buffer is immutable,
+        // so parsing again cannot fail
+        Apob::parse(&self.data).unwrap()
+    }
+
+    /// Returns the underlying buffer
+    pub fn into_vec(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// See [`Apob::header`]
+    pub fn header(&self) -> &ApobHeader {
+        self.as_apob().header()
+    }
+
+    /// See [`Apob::data`]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// See [`Apob::padding`]
+    pub fn padding(&self) -> &[u8] {
+        self.as_apob().padding()
+    }
+
+    /// See [`Apob::padding_is_zero`]
+    pub fn padding_is_zero(&self) -> bool {
+        self.as_apob().padding_is_zero()
+    }
+
+    /// See [`Apob::entries_offset`]
+    pub fn entries_offset(&self) -> usize {
+        self.as_apob().entries_offset()
+    }
+
+    /// See [`Apob::entries`]
+    pub fn entries(&self) -> ApobEntries<'_> {
+        self.as_apob().entries()
+    }
+
+    /// See [`Apob::entries_from`]
+    pub fn entries_from(&self, offset: usize) -> ApobEntries<'_> {
+        self.as_apob().entries_from(offset)
+    }
+
+    /// See [`Apob::cancelled_entries`]
+    pub fn cancelled_entries(
+        &self,
+    ) -> impl Iterator<Item = Result<(&ApobEntry, &[u8]), ApobError>> {
+        self.as_apob().cancelled_entries()
+    }
+
+    /// See [`Apob::iter_headers`]
+    pub fn iter_headers(
+        &self,
+    ) -> impl Iterator<Item = Result<&ApobEntry, ApobError>> {
+        self.as_apob().iter_headers()
+    }
+
+    /// See [`Apob::entry_at_offset`]
+    pub fn entry_at_offset(
+        &self,
+        offset: usize,
+    ) -> Option<(&ApobEntry, &[u8])> {
+        self.as_apob().entry_at_offset(offset)
+    }
+
+    /// See [`Apob::entry_count`]
+    pub fn entry_count(&self) -> Result<usize, ApobError> {
+        self.as_apob().entry_count()
+    }
+
+    /// See [`Apob::find`]
+    pub fn find(
+        &self,
+        group: ApobGroup,
+        ty: u32,
+        inst: Option<u32>,
+    ) -> impl Iterator<Item = Result<(&ApobEntry, &[u8]), ApobError>> {
+        self.as_apob().find(group, ty, inst)
+    }
+
+    /// See [`Apob::event_log`]
+    pub fn event_log(&self) -> Option<Result<&[ApobEvent], ApobError>> {
+        self.as_apob().event_log()
+    }
+
+    /// See [`Apob::ccx_coremap`]
+    pub fn ccx_coremap(&self) -> Option<Result<&MilanApobCoremap, ApobError>> {
+        self.as_apob().ccx_coremap()
+    }
+
+    /// See [`Apob::phy_override`]
+    pub fn phy_override(&self) -> Option<Result<&[u8], ApobError>> {
+        self.as_apob().phy_override()
+    }
+}
@@ -0,0 +1,47 @@
+//! Manual `Serialize` implementations for types which don't fit `derive`
+
+use crate::{MilanTrainErrorData0, MilanTrainErrorData1, PmuTfiEntryBitfield};
+
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+/// Serializes an array of any length as a sequence
+///
+/// `serde` only implements `Serialize` for arrays of up to 32 elements.
+pub(crate) fn array<S: Serializer, T: Serialize, const N: usize>(
+    a: &[T; N],
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    a.as_slice().serialize(s)
+}
+
+impl Serialize for PmuTfiEntryBitfield {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let mut out = s.serialize_struct("PmuTfiEntryBitfield", 5)?;
+        out.serialize_field("sock", &self.sock())?;
+        out.serialize_field("umc", &self.umc())?;
+        out.serialize_field("dimension", &self.dimension())?;
+        out.serialize_field("num_1d", &self.num_1d())?;
+        out.serialize_field("stage", &self.stage())?;
+        out.end()
+    }
+}
+
+impl Serialize for MilanTrainErrorData0 {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let mut out = s.serialize_struct("MilanTrainErrorData0", 4)?;
+        out.serialize_field("sock", &self.sock())?;
+        out.serialize_field("chan", &self.chan())?;
+        out.serialize_field("dimm", &self.dimm())?;
+        out.serialize_field("rank", &self.rank())?;
+        out.end()
+    }
+}
+
+impl Serialize for MilanTrainErrorData1 {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let mut out = s.serialize_struct("MilanTrainErrorData1", 2)?;
+        out.serialize_field("pmu_load", &self.pmu_load())?;
+        out.serialize_field("pmu_train", &self.pmu_train())?;
+        out.end()
+    }
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use apob::{Apob, ApobEntry, ApobError, ApobGroup};
+use std::mem::{offset_of, size_of};
+
+/// Builds a blob with two entries, returning it and the first entry's offset
+fn blob() -> (Vec<u8>, usize) {
+    let data = common::blob(&[
+        (ApobGroup::GENERAL, 1, 0, &[0; 8]),
+        (ApobGroup::GENERAL, 1, 1, &[0; 8]),
+    ]);
+    let pos = Apob::parse(&data).unwrap().entries_offset();
+    (data, pos)
+}
+
+/// Overwrites the `size` of the entry at `pos`
+fn set_size(data: &mut [u8], pos: usize, size: u32) {
+    common::set_u32(data, pos + offset_of!(ApobEntry, size), size);
+}
+
+#[test]
+fn entry_size_max() {
+    let (mut data, pos) = blob();
+    set_size(&mut data, pos, u32::MAX);
+    let apob = Apob::parse(&data).unwrap();
+    let mut entries = apob.entries();
+    assert_eq!(
+        entries.next().unwrap().unwrap_err(),
+        ApobError::OffsetOutOfBounds {
+            offset: pos.saturating_add(u32::MAX as usize),
+            len: data.len(),
+        }
+    );
+    assert!(entries.next().is_none());
+    assert!(apob.validate_layout().is_err());
+}
+
+#[test]
+fn entry_size_past_end() {
+    let (mut data, pos) = blob();
+    let size = data.len() - pos + 1;
+    set_size(&mut data, pos, size as u32);
+    let apob = Apob::parse(&data).unwrap();
+    assert_eq!(
+        apob.entry_count(),
+        Err(ApobError::OffsetOutOfBounds {
+            offset: data.len() + 1,
+            len: data.len(),
+        })
+    );
+}
+
+#[test]
+fn entry_size_smaller_than_header() {
+    let (mut data, pos) = blob();
+    set_size(&mut data, pos, size_of::<ApobEntry>() as u32 - 1);
+    let apob = Apob::parse(&data).unwrap();
+    assert!(apob.entries().next().unwrap().is_err());
+}
@@ -0,0 +1,56 @@
+//! Fixtures shared by the integration tests
+//!
+//! Each test binary only uses some of these.
+#![allow(dead_code)]
+
+use apob::{
+    ApobBuilder, ApobEvent, ApobGroup, ApobSysMemMap, ApobSysMemMapHole,
+    MilanApobEventLog, PmuTfi, PmuTfiEntry, APOB_VERSION,
+};
+use zerocopy::{FromZeros, IntoBytes};
+
+/// Builds a blob holding `(group, type, instance, payload)` entries, in order
+pub fn blob(entries: &[(ApobGroup, u32, u32, &[u8])]) -> Vec<u8> {
+    let mut builder = ApobBuilder::new(APOB_VERSION);
+    for &(group, ty, inst, data) in entries {
+        builder.push_entry(group, ty, inst, data);
+    }
+    builder.finish()
+}
+
+/// Overwrites a `u32` at `pos`
+pub fn set_u32(data: &mut [u8], pos: usize, v: u32) {
+    data[pos..pos + 4].copy_from_slice(&v.to_le_bytes());
+}
+
+/// Builds a Milan event log payload holding `events`
+pub fn event_log(events: &[ApobEvent]) -> Vec<u8> {
+    let mut log = MilanApobEventLog::new_zeroed();
+    log.count = events.len() as u16;
+    log.events[..events.len()].copy_from_slice(events);
+    log.as_bytes().to_vec()
+}
+
+/// Builds a system memory map payload, with `(base, size, ty)` for each hole
+pub fn mem_map(high_phys: u64, holes: &[(u64, u64, u32)]) -> Vec<u8> {
+    let mut map = ApobSysMemMap::new_zeroed();
+    map.high_phys = high_phys;
+    map.hole_count = holes.len() as u32;
+    let mut out = map.as_bytes().to_vec();
+    for &(base, size, ty) in holes {
+        let mut hole = ApobSysMemMapHole::new_zeroed();
+        hole.base = base;
+        hole.size = size;
+        hole.ty = ty;
+        out.extend_from_slice(hole.as_bytes());
+    }
+    out
+}
+
+/// Builds a PMU training failure payload whose valid entries are `entries`
+pub fn pmu_tfi(entries: &[PmuTfiEntry]) -> Vec<u8> {
+    let mut tfi = PmuTfi::new_zeroed();
+    tfi.nvalid = entries.len() as u32;
+    tfi.entries[..entries.len()].copy_from_slice(entries);
+    tfi.as_bytes().to_vec()
+}
@@ -0,0 +1,120 @@
+use apob::{
+    payload_as, Apob, ApobBuilder, ApobEntry, ApobError, ApobGroup,
+    APOB_VERSION,
+};
+use std::mem::size_of;
+use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout};
+
+const OFFSET: usize = 0x40;
+const HDR: usize = size_of::<ApobEntry>();
+
+/// Builds a blob with padding before two entries and junk after them
+fn blob() -> Vec<u8> {
+    let mut data = ApobBuilder::new(APOB_VERSION)
+        .offset(OFFSET as u32)
+        .push_entry(ApobGroup::GENERAL, 1, 0, &[1, 0, 0, 0, 2, 0, 0, 0])
+        .push_entry(ApobGroup::MEMORY, 2, 1, &[0; 4])
+        .finish();
+    data.extend_from_slice(&[0xff; 16]);
+    data
+}
+
+/// Returns the `(group, inst)` of the entry at `offset`
+fn at(data: &[u8], offset: usize) -> Option<(Option<ApobGroup>, u32)> {
+    let apob = Apob::parse(data).unwrap();
+    apob.entry_at_offset(offset)
+        .map(|(e, _)| (e.group(), e.inst))
+}
+
+#[test]
+fn header_and_padding() {
+    let data = blob();
+    assert_eq!(at(&data, 0), None);
+    assert_eq!(at(&data, 0x10), None);
+    assert_eq!(at(&data, OFFSET - 1), None);
+}
+
+#[test]
+fn entry_boundaries() {
+    let data = blob();
+    let first = Some((Some(ApobGroup::GENERAL), 0));
+    let second = Some((Some(ApobGroup::MEMORY), 1));
+    let end = OFFSET + HDR + 8;
+    assert_eq!(at(&data, OFFSET), first);
+    assert_eq!(at(&data, OFFSET + HDR), first);
+    assert_eq!(at(&data, end - 1), first);
+    assert_eq!(at(&data, end), second);
+    assert_eq!(at(&data, end + HDR + 4 - 1), second);
+}
+
+#[test]
+fn returns_payload() {
+    let data = blob();
+    let apob = Apob::parse(&data).unwrap();
+    let (_, payload) = apob.entry_at_offset(OFFSET + HDR + 8).unwrap();
+    assert_eq!(payload.len(), 4);
+}
+
+#[test]
+fn past_declared_size() {
+    let data = blob();
+    let declared = Apob::parse(&data).unwrap().header().size as usize;
+    assert_eq!(at(&data, declared), None);
+    assert_eq!(at(&data, data.len() - 1), None);
+    assert_eq!(at(&data, data.len()), None);
+    assert_eq!(at(&data, usize::MAX), None);
+}
+
+#[test]
+fn hmac_covered_range() {
+    let data = blob();
+    let apob = Apob::parse(&data).unwrap();
+    let end = OFFSET + HDR + 8;
+    let (first, _) = apob.entry_at_offset(OFFSET).unwrap();
+    assert_eq!(first.hmac_covered_range(OFFSET), OFFSET..end);
+    let (second, payload) = apob.entry_at_offset(end).unwrap();
+    let range = second.hmac_covered_range(end);
+    assert_eq!(range, end..end + HDR + 4);
+    assert_eq!(&data[range][HDR..], payload);
+    assert_eq!(second.hmac_covered_range(usize::MAX).end, usize::MAX);
+}
+
+/// Stands in for a payload type which the crate doesn't describe
+#[derive(Debug, FromBytes, KnownLayout, Immutable)]
+#[repr(C)]
+struct Custom {
+    a: u32,
+    b: u32,
+}
+
+#[test]
+fn payload_as_custom_type() {
+    let data = blob();
+    let apob = Apob::parse(&data).unwrap();
+    let (_, payload) = apob.entry_at_offset(OFFSET).unwrap();
+    let custom = payload_as::<Custom>(payload).unwrap();
+    assert_eq!((custom.a, custom.b), (1, 2));
+
+    let (_, payload) = apob.entry_at_offset(OFFSET + HDR + 8).unwrap();
+    assert_eq!(
+        payload_as::<Custom>(payload).unwrap_err(),
+        ApobError::Truncated {
+            name: "Custom",
+            needed: 8,
+            got: 4,
+        }
+    );
+}
+
+#[test]
+fn payload_as_misaligned() {
+    // Backed by `u32`s, so the bytes are aligned for `Custom`
+    let words = [0u32; 3];
+    assert_eq!(
+        payload_as::<Custom>(&words.as_bytes()[1..]).unwrap_err(),
+        ApobError::Misaligned {
+            name: "Custom",
+            offset: 1,
+        }
+    );
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use apob::{ApobGroup, MultiApob};
+
+/// Builds a blob with one entry, whose instance tells the images apart
+fn blob(inst: u32) -> Vec<u8> {
+    common::blob(&[(ApobGroup::GENERAL, 9, inst, &[0; 8])])
+}
+
+/// Returns the instance of the entry in each image
+fn instances(data: &[u8]) -> Vec<u32> {
+    MultiApob::new(data)
+        .map(|apob| {
+            let (entry, _) = apob.unwrap().entries().next().unwrap().unwrap();
+            entry.inst
+        })
+        .collect()
+}
+
+#[test]
+fn back_to_back() {
+    let data = [blob(0), blob(1)].concat();
+    assert_eq!(instances(&data), [0, 1]);
+}
+
+#[test]
+fn padding_between_images() {
+    let data = [blob(0), vec![0xff; 0x20], blob(1)].concat();
+    assert_eq!(instances(&data), [0, 1]);
+}
+
+#[test]
+fn unaligned_second_image() {
+    for pad in 1..4 {
+        let data = [blob(0), vec![0xff; pad], blob(1)].concat();
+        assert_eq!(instances(&data), [0, 1], "{pad} bytes of padding");
+    }
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use apob::{Apob, ApobError, ApobGroup, ApobHeader, APOB_SIG, APOB_VERSION};
+use std::mem::{offset_of, size_of};
+use zerocopy::FromBytes;
+
+/// Builds a blob with one entry right after the header, then overwrites the
+/// header's `offset`
+fn blob(offset: u32) -> Vec<u8> {
+    let mut data = common::blob(&[(ApobGroup::GENERAL, 1, 0, &[0; 8])]);
+    common::set_u32(&mut data, offset_of!(ApobHeader, offset), offset);
+    data
+}
+
+fn check_entries(apob: &Apob) {
+    assert_eq!(apob.entries_offset(), size_of::<ApobHeader>());
+    assert!(apob.padding().is_empty());
+    assert_eq!(apob.entry_count(), Ok(1));
+    assert_eq!(apob.validate_layout(), Ok(()));
+}
+
+#[test]
+fn offset_zero() {
+    let data = blob(0);
+    assert_eq!(
+        Apob::parse(&data).unwrap_err(),
+        ApobError::OffsetOutOfBounds {
+            offset: 0,
+            len: data.len()
+        }
+    );
+}
+
+#[test]
+fn offset_header_size() {
+    let data = blob(size_of::<ApobHeader>() as u32);
+    check_entries(&Apob::parse(&data).unwrap());
+}
+
+#[test]
+fn offset_inside_header() {
+    let data = blob(8);
+    assert_eq!(
+        Apob::parse(&data).unwrap_err(),
+        ApobError::OffsetOutOfBounds {
+            offset: 8,
+            len: data.len()
+        }
+    );
+}
+
+/// A header declaring `size` bytes with entries at `offset`
+fn header(size: u32, offset: u32) -> ApobHeader {
+    ApobHeader {
+        sig: APOB_SIG,
+        version: APOB_VERSION,
+        size,
+        offset,
+    }
+}
+
+#[test]
+fn validate_ok() {
+    let data = blob(size_of::<ApobHeader>() as u32);
+    let h = ApobHeader::read_from_prefix(&data).unwrap().0;
+    assert_eq!(h.validate(data.len()), Ok(()));
+    // A truncated capture still has a valid header
+    assert_eq!(h.validate(size_of::<ApobHeader>()), Ok(()));
+}
+
+#[test]
+fn validate_signature() {
+    let h = ApobHeader {
+        sig: *b"ABCD",
+        ..header(0x100, 0x10)
+    };
+    assert_eq!(h.validate(0x100), Err(ApobError::BadSignature(*b"ABCD")));
+}
+
+#[test]
+fn validate_version() {
+    let h = ApobHeader {
+        version: APOB_VERSION - 1,
+        ..header(0x100, 0x10)
+    };
+    assert_eq!(
+        h.validate(0x100),
+        Err(ApobError::BadVersion(APOB_VERSION - 1))
+    );
+}
+
+#[test]
+fn validate_offset_past_buffer() {
+    assert_eq!(
+        header(0x100, 0x80).validate(0x40),
+        Err(ApobError::OffsetOutOfBounds {
+            offset: 0x80,
+            len: 0x40
+        })
+    );
+    assert_eq!(
+        header(u32::MAX, u32::MAX).validate(0x40),
+        Err(ApobError::OffsetOutOfBounds {
+            offset: u32::MAX as usize,
+            len: 0x40
+        })
+    );
+}
+
+#[test]
+fn validate_size_before_entries() {
+    assert_eq!(
+        header(0x20, 0x30).validate(0x100),
+        Err(ApobError::OffsetOutOfBounds {
+            offset: 0x30,
+            len: 0x20
+        })
+    );
+    assert_eq!(
+        header(8, 0x10).validate(0x100),
+        Err(ApobError::OffsetOutOfBounds {
+            offset: 0x10,
+            len: 8
+        })
+    );
+}
+
+#[test]
+fn parse_size_before_entries() {
+    let mut data = blob(size_of::<ApobHeader>() as u32);
+    common::set_u32(&mut data, offset_of!(ApobHeader, size), 8);
+    assert!(Apob::parse(&data).is_err());
+    assert!(Apob::parse_any_version(&data).is_err());
+}
@@ -0,0 +1,434 @@
+mod common;
+
+use apob::{
+    Apob, ApobEntry, ApobError, ApobEvent, ApobFabricType, ApobGeneralType,
+    ApobGroup, ApobHeader, ApobMemoryType, ApobSysMemMap, ApobSysMemMapHole,
+    DecodedEvent, MilanApobEventClass, MilanApobEventInfo, MilanApobEventLog,
+    MilanTrainError, PmuTfi, PmuTfiEntry, PmuTfiEntryBitfield, APOB_CANCELLED,
+    APOB_SIG, APOB_VERSION,
+};
+use common::{event_log, set_u32};
+use std::mem::{offset_of, size_of};
+use zerocopy::{FromBytes, IntoBytes};
+
+const EVENTS: [ApobEvent; 3] = [
+    ApobEvent {
+        class: MilanApobEventClass::ALERT as u32,
+        info: 0x1234,
+        data0: 1,
+        data1: 2,
+    },
+    ApobEvent {
+        class: MilanApobEventClass::FATAL as u32,
+        info: 0x5678,
+        data0: 3,
+        data1: 4,
+    },
+    // Not a known class
+    ApobEvent {
+        class: 0x99,
+        info: 0x9abc,
+        data0: 5,
+        data1: 6,
+    },
+];
+
+const HIGH_PHYS: u64 = 0x10_8000_0000;
+
+/// `(base, size, ty)` for each hole
+const HOLES: [(u64, u64, u32); 3] = [
+    (0xa_0000, 0x6_0000, 1),
+    (0x8000_0000, 0x8000_0000, 2),
+    (0xfd_0000_0000, 0x3_0000_0000, 3),
+];
+
+/// Valid entries of the PMU training failure log
+const TFI: [PmuTfiEntry; 2] = [
+    PmuTfiEntry {
+        bits: PmuTfiEntryBitfield(0x0001_8023),
+        error: 0x11,
+        data: [0; 4],
+    },
+    PmuTfiEntry {
+        bits: PmuTfiEntryBitfield(0x0002_0001),
+        error: 0x22,
+        data: [1; 4],
+    },
+];
+
+fn mem_map() -> Vec<u8> {
+    common::mem_map(HIGH_PHYS, &HOLES)
+}
+
+fn pmu_tfi() -> Vec<u8> {
+    common::pmu_tfi(&TFI)
+}
+
+/// Builds a blob whose first entry is a cancelled event log, returning it and
+/// that entry's offset
+fn blob() -> (Vec<u8>, usize) {
+    let log = ApobGeneralType::EVENT_LOG as u32;
+    let mut data = common::blob(&[
+        (ApobGroup::GENERAL, log, 0, &event_log(&EVENTS[..1])),
+        (ApobGroup::GENERAL, log, 0, &event_log(&EVENTS)),
+        (
+            ApobGroup::FABRIC,
+            ApobFabricType::SYS_MEM_MAP as u32,
+            0,
+            &mem_map(),
+        ),
+        (
+            ApobGroup::MEMORY,
+            ApobMemoryType::MILAN_PMU_TRAIN_FAIL as u32,
+            1,
+            &pmu_tfi(),
+        ),
+    ]);
+    let pos = Apob::parse(&data).unwrap().entries_offset();
+    let group = APOB_CANCELLED | ApobGroup::GENERAL as u32;
+    set_u32(&mut data, pos + offset_of!(ApobEntry, group), group);
+    (data, pos)
+}
+
+#[test]
+fn header() {
+    let (data, pos) = blob();
+    let apob = Apob::parse(&data).unwrap();
+    let header = apob.header();
+    assert_eq!(header.sig, APOB_SIG);
+    assert_eq!({ header.version }, APOB_VERSION);
+    assert_eq!(header.size as usize, data.len());
+    assert_eq!(pos, size_of::<ApobHeader>());
+    assert!(apob.padding_is_zero());
+    assert_eq!(apob.validate_layout(), Ok(()));
+}
+
+#[test]
+fn entries() {
+    let (data, _) = blob();
+    let apob = Apob::parse(&data).unwrap();
+    let entries = apob.entries().collect::<Result<Vec<_>, _>>().unwrap();
+    let found = entries
+        .iter()
+        .map(|(e, data)| {
+            (e.group(), e.type_id(), e.inst, e.cancelled(), data.len())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        found,
+        [
+            (
+                Some(ApobGroup::GENERAL),
+                ApobGeneralType::EVENT_LOG as u32,
+                0,
+                true,
+                size_of::<MilanApobEventLog>()
+            ),
+            (
+                Some(ApobGroup::GENERAL),
+                ApobGeneralType::EVENT_LOG as u32,
+                0,
+                false,
+                size_of::<MilanApobEventLog>()
+            ),
+            (
+                Some(ApobGroup::FABRIC),
+                ApobFabricType::SYS_MEM_MAP as u32,
+                0,
+                false,
+                size_of::<ApobSysMemMap>()
+                    + HOLES.len() * size_of::<ApobSysMemMapHole>()
+            ),
+            (
+                Some(ApobGroup::MEMORY),
+                ApobMemoryType::MILAN_PMU_TRAIN_FAIL as u32,
+                1,
+                false,
+                size_of::<PmuTfi>()
+            ),
+        ]
+    );
+    assert_eq!(apob.entry_count(), Ok(4));
+    assert_eq!(apob.cancelled_entries().count(), 1);
+}
+
+#[test]
+fn odd_length_payload() {
+    let data = common::blob(&[
+        (ApobGroup::MEMORY, 0x77, 0, &[1, 2, 3]),
+        (
+            ApobGroup::FABRIC,
+            ApobFabricType::SYS_MEM_MAP as u32,
+            0,
+            &mem_map(),
+        ),
+    ]);
+    let apob = Apob::parse(&data).unwrap();
+    let entries = apob.entries().collect::<Result<Vec<_>, _>>().unwrap();
+    // The short payload is zero-padded, keeping the next entry aligned
+    let (entry, payload) = entries[0];
+    assert_eq!(entry.size as usize, size_of::<ApobEntry>() + 4);
+    assert_eq!(payload, [1, 2, 3, 0]);
+    let (entry, payload) = entries[1];
+    assert_eq!(entry.type_id(), ApobFabricType::SYS_MEM_MAP as u32);
+    assert_eq!(payload, mem_map());
+    assert_eq!(apob.validate_layout(), Ok(()));
+}
+
+#[test]
+fn unaligned_buffer() {
+    let (data, _) = blob();
+    // Whatever the allocation's alignment, most of these shifts misalign it
+    for shift in 1..4 {
+        let buf = [&vec![0; shift][..], &data].concat();
+        let apob = Apob::parse(&buf[shift..]).unwrap();
+        assert_eq!(apob.entry_count(), Ok(4));
+        let events = apob.event_log().unwrap().unwrap();
+        assert_eq!(events.as_bytes(), EVENTS.as_bytes());
+        let (entry, payload) = apob
+            .find(
+                ApobGroup::MEMORY,
+                ApobMemoryType::MILAN_PMU_TRAIN_FAIL as u32,
+                None,
+            )
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!({ entry.inst }, 1);
+        assert_eq!(payload, pmu_tfi());
+    }
+}
+
+#[test]
+fn event_log_skips_cancelled() {
+    let (data, _) = blob();
+    let apob = Apob::parse(&data).unwrap();
+    let events = apob.event_log().unwrap().unwrap();
+    assert_eq!(events.as_bytes(), EVENTS.as_bytes());
+    let classes = events.iter().map(|e| e.class()).collect::<Vec<_>>();
+    assert_eq!(
+        classes,
+        [
+            Some(MilanApobEventClass::ALERT),
+            Some(MilanApobEventClass::FATAL),
+            None
+        ]
+    );
+    // The unknown class is above FATAL, so it counts as more severe
+    assert!(events[2].is_at_least(MilanApobEventClass::FATAL));
+}
+
+#[test]
+fn decoded_events() {
+    let train_error = ApobEvent {
+        class: MilanApobEventClass::ERROR as u32,
+        info: MilanApobEventInfo::TRAIN_ERROR as u32,
+        data0: 0x0102_0300,
+        data1: 0x3,
+    };
+    let data = event_log(&[EVENTS[2], train_error]);
+    let log = MilanApobEventLog::try_from(&data[..]).unwrap();
+    let events = log.decoded().collect::<Vec<_>>();
+    assert_eq!(
+        events,
+        [
+            DecodedEvent {
+                class: Err(0x99),
+                info: Err(0x9abc),
+                data0: 5,
+                data1: 6,
+                train_error: None,
+            },
+            DecodedEvent {
+                class: Ok(MilanApobEventClass::ERROR),
+                info: Ok(MilanApobEventInfo::TRAIN_ERROR),
+                data0: 0x0102_0300,
+                data1: 0x3,
+                train_error: Some(MilanTrainError {
+                    sock: 0,
+                    chan: 3,
+                    dimm: 2,
+                    rank: 1,
+                    pmu_load: true,
+                    pmu_train: true,
+                }),
+            },
+        ]
+    );
+}
+
+#[test]
+fn event_data_by_info_code() {
+    let train_error = MilanApobEventInfo::TRAIN_ERROR as u32;
+    assert_eq!(
+        apob::decode_event_data(train_error, 0x0102_0300, 0x1),
+        ["sock: 0  chan: 3", "dimm: 2  rank: 1", "PMU load error"]
+    );
+    assert_eq!(
+        apob::decode_event_data(train_error, 0, 0),
+        ["sock: 0  chan: 0", "dimm: 0  rank: 0"]
+    );
+    // Codes without an entry in the table have nothing to describe
+    assert!(apob::decode_event_data(0x1234, 1, 2).is_empty());
+}
+
+#[test]
+fn find_ignores_cancelled_type_bits() {
+    let ty = ApobFabricType::SYS_MEM_MAP as u32;
+    let data = common::blob(&[(
+        ApobGroup::FABRIC,
+        APOB_CANCELLED | ty,
+        0,
+        &mem_map(),
+    )]);
+    let apob = Apob::parse(&data).unwrap();
+    let (entry, _) = apob
+        .find(ApobGroup::FABRIC, ty, None)
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!({ entry.ty }, APOB_CANCELLED | ty);
+}
+
+#[test]
+fn mem_map_holes() {
+    let (data, _) = blob();
+    let apob = Apob::parse(&data).unwrap();
+    let (_, payload) = apob
+        .find(ApobGroup::FABRIC, ApobFabricType::SYS_MEM_MAP as u32, None)
+        .next()
+        .unwrap()
+        .unwrap();
+    let map = ApobSysMemMap::try_from(payload).unwrap();
+    assert_eq!({ map.high_phys }, HIGH_PHYS);
+    let (holes, _) = <[ApobSysMemMapHole]>::ref_from_prefix_with_elems(
+        &payload[size_of::<ApobSysMemMap>()..],
+        map.hole_count as usize,
+    )
+    .unwrap();
+    let found = holes
+        .iter()
+        .map(|h| (h.base, h.size, h.ty))
+        .collect::<Vec<_>>();
+    assert_eq!(found, HOLES);
+    let holes_below = HOLES[..2].iter().map(|(_, size, _)| size).sum::<u64>();
+    assert_eq!(map.usable_bytes(holes), HIGH_PHYS - holes_below);
+}
+
+#[test]
+fn pmu_training_failures() {
+    let (data, _) = blob();
+    let apob = Apob::parse(&data).unwrap();
+    let (entry, payload) = apob
+        .find(
+            ApobGroup::MEMORY,
+            ApobMemoryType::MILAN_PMU_TRAIN_FAIL as u32,
+            Some(1),
+        )
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!({ entry.inst }, 1);
+    let tfi = PmuTfi::try_from(payload).unwrap();
+    assert_eq!(tfi.nvalid as usize, TFI.len());
+    let e = &tfi.entries[0];
+    assert_eq!(
+        (
+            e.bits.sock(),
+            e.bits.umc(),
+            e.bits.dimension(),
+            e.bits.num_1d(),
+            e.bits.stage()
+        ),
+        (1, 1, 0, 1, 3)
+    );
+    assert_eq!({ e.error }, 0x11);
+    assert_eq!({ tfi.entries[1].data }, [1; 4]);
+}
+
+#[test]
+fn bad_signature() {
+    let (mut data, _) = blob();
+    data[..4].copy_from_slice(b"ABCD");
+    assert_eq!(
+        Apob::parse(&data).unwrap_err(),
+        ApobError::BadSignature(*b"ABCD")
+    );
+}
+
+#[test]
+fn byte_swapped() {
+    let (mut data, _) = blob();
+    data[..4].copy_from_slice(b"BOPA");
+    assert_eq!(Apob::parse(&data).unwrap_err(), ApobError::ByteSwapped);
+}
+
+#[test]
+fn bad_version() {
+    let (mut data, _) = blob();
+    set_u32(&mut data, offset_of!(ApobHeader, version), APOB_VERSION + 1);
+    assert_eq!(
+        Apob::parse(&data).unwrap_err(),
+        ApobError::BadVersion(APOB_VERSION + 1)
+    );
+    assert!(Apob::parse_any_version(&data).is_ok());
+}
+
+#[test]
+fn truncated_header() {
+    let (data, _) = blob();
+    assert_eq!(
+        Apob::parse(&data[..8]).unwrap_err(),
+        ApobError::Truncated {
+            name: "ApobHeader",
+            needed: size_of::<ApobHeader>(),
+            got: 8,
+        }
+    );
+}
+
+#[test]
+fn event_count_too_large() {
+    let (mut data, pos) = blob();
+    // The live event log follows the cancelled one
+    let log = pos + size_of::<ApobEntry>() * 2 + size_of::<MilanApobEventLog>();
+    data[log..log + 2].copy_from_slice(&65u16.to_le_bytes());
+    let apob = Apob::parse(&data).unwrap();
+    assert_eq!(
+        apob.event_log().unwrap().unwrap_err(),
+        ApobError::InvalidCount { count: 65, max: 64 }
+    );
+}
+
+#[test]
+fn event_log_truncated() {
+    let log = event_log(&EVENTS);
+    assert_eq!(
+        apob::event_log(&log[..100]).unwrap_err(),
+        ApobError::Truncated {
+            name: "MilanApobEventLog",
+            needed: size_of::<MilanApobEventLog>(),
+            got: 100,
+        }
+    );
+}
+
+#[test]
+fn hole_count_too_large() {
+    let mut map = mem_map();
+    set_u32(&mut map, offset_of!(ApobSysMemMap, hole_count), 4);
+    assert_eq!(
+        ApobSysMemMap::try_from(&map[..]).unwrap_err(),
+        ApobError::InvalidCount { count: 4, max: 3 }
+    );
+}
+
+#[test]
+fn pmu_nvalid_too_large() {
+    let mut tfi = pmu_tfi();
+    set_u32(&mut tfi, offset_of!(PmuTfi, nvalid), 41);
+    assert_eq!(
+        PmuTfi::try_from(&tfi[..]).unwrap_err(),
+        ApobError::InvalidCount { count: 41, max: 40 }
+    );
+}
@@ -0,0 +1,77 @@
+//! Throws arbitrary bytes at the parser and every typed accessor
+//!
+//! Any input may be rejected with an `ApobError`, but none may panic.  Run
+//! it from the repository root with
+//!
+//! ```text
+//! cargo +nightly fuzz run parse fuzz/corpus/parse fuzz/seeds/parse
+//! ```
+//!
+//! `fuzz/seeds/parse` holds real blobs along with inputs exercising each
+//! count and size field which could over-read.
+
+#![no_main]
+
+use apob::{
+    ApcbHeader, Apob, ApobHeader, ApobOwned, ApobSysMemMap, ApobSysMemMapHole,
+    MilanApobCoremap, MilanApobEventClass, MilanApobEventLog, MultiApob,
+    PmuTfi, SmbiosStructures,
+};
+use libfuzzer_sys::fuzz_target;
+
+/// Decodes a payload as every type we know about, whatever its real type
+fn payload(data: &[u8]) {
+    for e in apob::event_log(data).into_iter().flatten() {
+        let _ = apob::event_info_name(e.info);
+        apob::decode_event_data(e.info, e.data0, e.data1);
+    }
+    if let Ok(log) = MilanApobEventLog::try_from(data) {
+        log.events_at_least(MilanApobEventClass::ERROR).count();
+    }
+    if let Ok(map) = ApobSysMemMap::try_from(data) {
+        let (_, rest) = apob::parse_prefix::<ApobSysMemMap>(data).unwrap();
+        let holes = rest
+            .chunks_exact(core::mem::size_of::<ApobSysMemMapHole>())
+            .filter_map(|h| apob::parse_prefix(h).ok().map(|(h, _)| *h))
+            .collect::<Vec<ApobSysMemMapHole>>();
+        // Bound the walk, since each step is quadratic in the hole count
+        map.usable_ranges(&holes).take(256).count();
+    }
+    if let Ok(map) = MilanApobCoremap::try_from(data) {
+        for (_, _, core) in map.present_cores() {
+            core.threads().count();
+        }
+    }
+    let _ = PmuTfi::try_from(data);
+    let _ = ApcbHeader::parse(data);
+    for s in SmbiosStructures::new(data).flatten() {
+        s.strings().count();
+    }
+}
+
+fn blob(apob: &Apob) {
+    apob.padding_is_zero();
+    let _ = apob.entry_count();
+    let _ = apob.entry_at_offset(apob.data().len() / 2);
+    let _ = apob.validate_layout();
+    for (_, data) in apob.entries().flatten() {
+        payload(data);
+    }
+    let _ = apob.event_log();
+    let _ = apob.ccx_coremap();
+    let _ = apob.phy_override();
+}
+
+fuzz_target!(|data: &[u8]| {
+    let _ = ApobHeader::try_from(data);
+    if let Ok(apob) = Apob::parse(data) {
+        blob(&apob);
+    }
+    if let Ok(owned) = ApobOwned::from_slice(data) {
+        blob(&owned.as_apob());
+    }
+    for apob in MultiApob::new(data).flatten() {
+        blob(&apob);
+    }
+    payload(data);
+});